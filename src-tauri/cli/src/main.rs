@@ -0,0 +1,278 @@
+//! Headless front end to the engine for scripted planning (CI, batch runs).
+//! Reads JSON or TOML input and writes JSON (or a plan report) to stdout or `--output`.
+
+use endfield_engine::data_loader::{DataLoader, DATABASE_PATH_VAR};
+use endfield_engine::db_validation::{self, Severity};
+use endfield_engine::layout_generator::{LayoutCandidate, LayoutConstraints, LayoutGenerator};
+use endfield_engine::logging::{LogOptions, Logging};
+use endfield_engine::plan_export::ExportFormat;
+use endfield_engine::plates::ProjectSummary;
+use endfield_engine::project::ProjectFile;
+use endfield_engine::rate::Rate;
+use endfield_engine::recipe_solver::{ProductionPlan, RecipeSolver};
+use endfield_engine::simulation_stats::SimulationReport;
+use endfield_engine::terrain::PlateDefinition;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: endfield-cli [--db <database.json>] <command> [options]
+
+Commands:
+  solve <input>        Solve a production plan
+  layout <input>       Solve, then generate layouts for the plan
+  simulate <project>   Run a saved project headlessly and report production
+  validate-db          Check database.json for broken entries and references
+
+Input files are JSON, or TOML when the name ends in .toml; `-` reads JSON from stdin.
+Target rates are numbers (per minute) or strings with a unit: \"0.5/s\", \"30/min\", \"1800/h\".
+
+Options:
+  --db <path>          Database to use instead of the default lookup
+  -o, --output <path>  Write the result to a file instead of stdout
+  --format <format>    solve only: json (default), csv or markdown
+  --seconds <n>        simulate only: simulated time (default 60)
+  --step <n>           simulate only: step length in seconds (default 0.05)
+
+Diagnostics go to stderr; set ENDFIELD_LOG (e.g. debug) for more than warnings.";
+
+const DEFAULT_SIM_SECONDS: f64 = 60.0;
+const DEFAULT_SIM_STEP: f64 = 0.05;
+
+#[derive(Debug, Deserialize)]
+struct Target {
+    item_id: String,
+    /// `30` (per minute), `"0.5/s"`, `"1800/h"` or `{ amount, unit }`
+    #[serde(alias = "per_minute")]
+    rate: Rate,
+}
+
+/// Input of `solve` and `layout`
+#[derive(Debug, Deserialize)]
+struct PlanInput {
+    targets: Vec<Target>,
+    /// Optional when `plate_definition` is given
+    plate_width: Option<i32>,
+    plate_height: Option<i32>,
+    /// Plate definition file; its size and terrain constrain the layout
+    plate_definition: Option<PathBuf>,
+    #[serde(default = "default_candidates")]
+    num_candidates: usize,
+}
+
+fn default_candidates() -> usize {
+    1
+}
+
+#[derive(Debug, Serialize)]
+struct LayoutOutput {
+    plan: ProductionPlan,
+    candidates: Vec<LayoutCandidate>,
+}
+
+#[derive(Debug, Serialize)]
+struct PlateReport {
+    plate: String,
+    report: SimulationReport,
+}
+
+#[derive(Debug, Serialize)]
+struct SimulateOutput {
+    seconds: f64,
+    summary: ProjectSummary,
+    reports: Vec<PlateReport>,
+}
+
+#[derive(Debug)]
+struct Args {
+    command: String,
+    input: Option<String>,
+    output: Option<PathBuf>,
+    format: ExportFormat,
+    seconds: f64,
+    step: f64,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut parsed = Args {
+            command: String::new(),
+            input: None,
+            output: None,
+            format: ExportFormat::Json,
+            seconds: DEFAULT_SIM_SECONDS,
+            step: DEFAULT_SIM_STEP,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "-h" | "--help" => return Err(String::new()),
+                "--db" => std::env::set_var(DATABASE_PATH_VAR, value()?),
+                "-o" | "--output" => parsed.output = Some(PathBuf::from(value()?)),
+                "--format" => {
+                    let format = value()?;
+                    parsed.format = serde_json::from_value(serde_json::Value::String(format.clone()))
+                        .map_err(|_| format!("Unknown format {}", format))?;
+                }
+                "--seconds" => parsed.seconds = number(&arg, &value()?)?,
+                "--step" => parsed.step = number(&arg, &value()?)?,
+                flag if flag.starts_with("--") => return Err(format!("Unknown option {}", flag)),
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        parsed.command = positional.next().ok_or("Missing command")?;
+        parsed.input = positional.next();
+        if let Some(extra) = positional.next() {
+            return Err(format!("Unexpected argument {}", extra));
+        }
+        Ok(parsed)
+    }
+
+    fn input(&self) -> Result<&str, String> {
+        self.input.as_deref().ok_or_else(|| format!("{} needs an input file", self.command))
+    }
+}
+
+fn number(flag: &str, value: &str) -> Result<f64, String> {
+    value.parse::<f64>().ok()
+        .filter(|n| n.is_finite() && *n > 0.0)
+        .ok_or_else(|| format!("{} must be a positive number, got {}", flag, value))
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        // An empty message means --help was asked for
+        Err(message) if message.is_empty() => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    // Warnings only unless ENDFIELD_LOG asks for more; stdout is reserved for results
+    let mut log_options = LogOptions::new("endfield-cli");
+    log_options.filter = "warn".to_string();
+    let _logging = match Logging::init(log_options) {
+        Ok(logging) => logging,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&args) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<ExitCode, String> {
+    match args.command.as_str() {
+        "solve" => {
+            let input: PlanInput = read_input(args.input()?)?;
+            let (plan, _) = solve(&input)?;
+            write_output(&plan.export(args.format)?, args.output.as_deref())?;
+        }
+        "layout" => {
+            let input: PlanInput = read_input(args.input()?)?;
+            let (plan, constraints) = solve(&input)?;
+            let generator = LayoutGenerator::new(constraints, DataLoader::load_geometry()?);
+            let required = plan.required_facilities.iter()
+                .map(|req| (req.facility_id.clone(), req.facility_type.clone(), req.count))
+                .collect();
+            let rates: Vec<(String, Rate)> = plan.actual_rates.iter().map(|(k, v)| (k.clone(), *v)).collect();
+            let candidates = generator.generate_layouts(required, &rates, input.num_candidates);
+            write_output(&to_json(&LayoutOutput { plan, candidates })?, args.output.as_deref())?;
+        }
+        "simulate" => {
+            let project: serde_json::Value = read_input(args.input()?)?;
+            let project = ProjectFile::from_json(&project.to_string())?;
+            write_output(&to_json(&simulate(&project, args.seconds, args.step)?)?, args.output.as_deref())?;
+        }
+        "validate-db" => {
+            let issues = db_validation::validate_database(&DataLoader::load_database()?);
+            write_output(&to_json(&issues)?, args.output.as_deref())?;
+            if issues.iter().any(|i| i.severity == Severity::Error) {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        other => return Err(format!("Unknown command {}\n\n{}", other, USAGE)),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Solves the input's targets and returns the layout constraints for its plate
+fn solve(input: &PlanInput) -> Result<(ProductionPlan, LayoutConstraints), String> {
+    let definition = input.plate_definition.as_deref().map(PlateDefinition::load).transpose()?;
+    let terrain = definition.as_ref().map(PlateDefinition::terrain).transpose()?.unwrap_or_default();
+    let size = definition.as_ref().map(|d| d.size());
+    let plate_width = input.plate_width.or(size.map(|(w, _)| w as i32)).ok_or("plate_width is required")?;
+    let plate_height = input.plate_height.or(size.map(|(_, h)| h as i32)).ok_or("plate_height is required")?;
+
+    let targets = input.targets.iter().map(|t| (t.item_id.clone(), t.rate)).collect();
+    let mut constraints = LayoutConstraints::for_plate(plate_width, plate_height);
+    constraints.terrain = terrain;
+    let plan = RecipeSolver::from_database()?.solve_for_layout(targets, &constraints)?;
+    Ok((plan, constraints))
+}
+
+fn simulate(project: &ProjectFile, seconds: f64, step: f64) -> Result<SimulateOutput, String> {
+    let config = DataLoader::load_config()?;
+    let geometry = DataLoader::load_geometry()?;
+    let recipes = DataLoader::load_recipes()?;
+    let mut plates = project.restore(&config, &geometry)?;
+
+    let mut elapsed = 0.0;
+    while elapsed < seconds {
+        let dt = step.min(seconds - elapsed);
+        plates.step(&recipes, dt);
+        elapsed += dt;
+    }
+
+    let reports = plates.plates.iter().map(|plate| PlateReport {
+        plate: plate.name.clone(),
+        report: plate.grid.stats.report(&plate.grid.placed_facilities, &plate.grid.logistics_edges, &recipes, None),
+    }).collect();
+    Ok(SimulateOutput { seconds: elapsed, summary: plates.summary(), reports })
+}
+
+fn read_input<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let text = if path == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map_err(|e| format!("Failed to read stdin: {}", e))?;
+        text
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?
+    };
+    if path.ends_with(".toml") {
+        toml::from_str(&text).map_err(|e| format!("Invalid TOML in {}: {}", path, e))
+    } else {
+        serde_json::from_str(&text).map_err(|e| format!("Invalid JSON in {}: {}", path, e))
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize output: {}", e))
+}
+
+fn write_output(text: &str, output: Option<&Path>) -> Result<(), String> {
+    match output {
+        Some(path) => std::fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e)),
+        None => writeln!(std::io::stdout().lock(), "{}", text.trim_end())
+            .map_err(|e| format!("Failed to write output: {}", e)),
+    }
+}
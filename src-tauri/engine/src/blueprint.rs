@@ -0,0 +1,133 @@
+use crate::error::EngineError;
+use crate::facility::PlacedFacility;
+use crate::geometry;
+use crate::grid::GridState;
+use crate::grid_edit::GridEdit;
+use crate::logistics::LogisticsEdge;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+
+pub const BLUEPRINT_VERSION: u32 = 1;
+
+/// A copied sub-layout. Positions are relative to the selection's top-left corner,
+/// buffers and crafting state are dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Blueprint {
+    pub version: u32,
+    pub width: u32,
+    pub height: u32,
+    pub facilities: Vec<PlacedFacility>,
+    pub edges: Vec<LogisticsEdge>,
+}
+
+impl Blueprint {
+    /// Copies every facility lying fully inside the rectangle, plus the edges between them
+    pub fn capture(grid: &GridState, geometry: &serde_json::Value, x: i32, y: i32, width: u32, height: u32) -> Self {
+        let facilities: Vec<PlacedFacility> = grid.placed_facilities.iter()
+            .filter(|f| {
+                let (w, h) = geometry::footprint(geometry, &f.facility_id, f.rotation);
+                f.x >= x && f.y >= y && f.x + w as i32 <= x + width as i32 && f.y + h as i32 <= y + height as i32
+            })
+            .map(|f| PlacedFacility {
+                rotation: f.rotation,
+                port_settings: f.port_settings.clone(),
+                locked_recipe_id: f.locked_recipe_id.clone(),
+                ..PlacedFacility::new(&f.instance_id, &f.facility_id, f.x - x, f.y - y)
+            })
+            .collect();
+
+        let selected: Vec<&str> = facilities.iter().map(|f| f.instance_id.as_str()).collect();
+        let edges = grid.logistics_edges.iter()
+            .filter(|e| selected.contains(&e.from_instance_id.as_str()) && selected.contains(&e.to_instance_id.as_str()))
+            .cloned()
+            .collect();
+
+        Self { version: BLUEPRINT_VERSION, width, height, facilities, edges }
+    }
+
+    /// JSON, deflated, then URL-safe base64 so it survives chat and forum posts
+    pub fn encode(&self) -> Result<String, EngineError> {
+        let json = serde_json::to_vec(self).map_err(|e| EngineError::invalid(format!("Failed to serialize blueprint: {}", e)))?;
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&json).map_err(|e| EngineError::invalid(format!("Failed to compress blueprint: {}", e)))?;
+        let compressed = encoder.finish().map_err(|e| EngineError::invalid(format!("Failed to compress blueprint: {}", e)))?;
+        Ok(URL_SAFE_NO_PAD.encode(compressed))
+    }
+
+    pub fn decode(text: &str) -> Result<Self, EngineError> {
+        let compressed = URL_SAFE_NO_PAD.decode(text.trim())
+            .map_err(|e| EngineError::invalid(format!("Not a blueprint string: {}", e)))?;
+        let mut json = Vec::new();
+        DeflateDecoder::new(compressed.as_slice()).read_to_end(&mut json)
+            .map_err(|e| EngineError::invalid(format!("Corrupt blueprint: {}", e)))?;
+        let blueprint: Self = serde_json::from_slice(&json)
+            .map_err(|e| EngineError::invalid(format!("Invalid blueprint: {}", e)))?;
+        if blueprint.version > BLUEPRINT_VERSION {
+            return Err(EngineError::invalid(format!("Blueprint version {} is newer than supported ({})", blueprint.version, BLUEPRINT_VERSION)));
+        }
+        Ok(blueprint)
+    }
+
+    /// The blueprint turned clockwise by `rotation` degrees about its own box
+    pub fn rotated(&self, geometry: &serde_json::Value, rotation: u32) -> Self {
+        let mut out = self.clone();
+        for _ in 0..(rotation / 90) % 4 {
+            for f in &mut out.facilities {
+                let (_, h) = geometry::footprint(geometry, &f.facility_id, f.rotation);
+                let x = out.height as i32 - f.y - h as i32;
+                f.y = f.x;
+                f.x = x;
+                f.rotation = (f.rotation + 90) % 360;
+            }
+            std::mem::swap(&mut out.width, &mut out.height);
+        }
+        out
+    }
+
+    /// Edits that place a copy with its top-left corner at (x, y), with fresh instance ids
+    /// and the internal edges remapped to them
+    pub fn paste_edits(&self, grid: &GridState, geometry: &serde_json::Value, x: i32, y: i32, rotation: u32) -> Result<Vec<GridEdit>, EngineError> {
+        if !rotation.is_multiple_of(90) {
+            return Err(EngineError::invalid(format!("Rotation must be a multiple of 90, got {}", rotation)));
+        }
+        let blueprint = self.rotated(geometry, rotation);
+
+        let mut reserved = HashSet::new();
+        let mut id_map: HashMap<String, String> = HashMap::new();
+        let mut edits = Vec::new();
+
+        for f in &blueprint.facilities {
+            let instance_id = grid.next_instance_id_excluding(&f.facility_id, &reserved);
+            reserved.insert(instance_id.clone());
+            id_map.insert(f.instance_id.clone(), instance_id.clone());
+            edits.push(GridEdit::PlaceFacility {
+                facility: PlacedFacility { instance_id, x: x + f.x, y: y + f.y, ..f.clone() },
+            });
+        }
+
+        for e in &blueprint.edges {
+            let (Some(from), Some(to)) = (id_map.get(&e.from_instance_id), id_map.get(&e.to_instance_id)) else {
+                continue;
+            };
+            edits.push(GridEdit::AddEdge {
+                edge: LogisticsEdge { from_instance_id: from.clone(), to_instance_id: to.clone(), ..e.clone() },
+            });
+        }
+
+        Ok(edits)
+    }
+
+    /// Places a copy with its top-left corner at (x, y). Nothing is placed if any facility
+    /// would collide or leave the plate. Returns the new instance ids.
+    pub fn paste(&self, grid: &mut GridState, geometry: &serde_json::Value, x: i32, y: i32, rotation: u32) -> Result<Vec<String>, EngineError> {
+        let edits = self.paste_edits(grid, geometry, x, y, rotation)?;
+        let changes = grid.apply_edits(edits, geometry)?;
+        Ok(changes.iter().flat_map(|c| c.placed.iter().map(|f| f.instance_id.clone())).collect())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::blueprint::Blueprint;
+use crate::data_loader::DataLoader;
+use crate::facility::PlacedFacility;
+use crate::grid::GridState;
+use crate::logistics::LogisticsEdge;
+
+/// PAC feeding a Refining Unit at (2, 2), plus an unrelated furnace outside the selection
+fn source_grid() -> GridState {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    grid.placed_facilities = vec![
+        PlacedFacility::new("pac", "hub_pac_main", 2, 2),
+        PlacedFacility::new("furnace", "item_port_furnance_1", 12, 2),
+        PlacedFacility::new("other", "item_port_furnance_1", 20, 20),
+    ];
+    grid.placed_facilities[1].locked_recipe_id = Some("furnance_iron_nugget_1".to_string());
+    grid.logistics_edges = vec![LogisticsEdge::new("pac", "out_r1", "furnace", "in_1", "item_iron_ore")];
+    grid.resize_buffers(&geometry);
+    grid.rebuild_occupancy(&geometry);
+    grid
+}
+
+#[test]
+fn test_capture_encode_round_trip() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let blueprint = Blueprint::capture(&source_grid(), &geometry, 2, 2, 13, 9);
+
+    assert_eq!(blueprint.facilities.len(), 2);
+    assert_eq!((blueprint.facilities[0].x, blueprint.facilities[0].y), (0, 0));
+    assert_eq!((blueprint.facilities[1].x, blueprint.facilities[1].y), (10, 0));
+    assert_eq!(blueprint.edges.len(), 1);
+
+    let text = blueprint.encode().unwrap();
+    assert!(text.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+    let decoded = Blueprint::decode(&text).unwrap();
+    assert_eq!(decoded.facilities.len(), 2);
+    assert_eq!(decoded.facilities[1].locked_recipe_id.as_deref(), Some("furnance_iron_nugget_1"));
+    assert!(Blueprint::decode("not a blueprint").is_err());
+}
+
+#[test]
+fn test_paste_remaps_ids_and_rotates() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let blueprint = Blueprint::capture(&source_grid(), &geometry, 2, 2, 13, 9);
+    let mut grid = source_grid();
+
+    let ids = blueprint.paste(&mut grid, &geometry, 0, 14, 90).unwrap();
+
+    assert_eq!(ids, vec!["hub_pac_main_0", "item_port_furnance_1_0"]);
+    let pac = grid.placed_facilities.iter().find(|f| f.instance_id == ids[0]).unwrap();
+    let furnace = grid.placed_facilities.iter().find(|f| f.instance_id == ids[1]).unwrap();
+    // Clockwise: the furnace to the right of the PAC ends up below it
+    assert_eq!((pac.x, pac.y, pac.rotation), (0, 14, 90));
+    assert_eq!((furnace.x, furnace.y, furnace.rotation), (6, 24, 90));
+    assert_eq!(furnace.input_buffer.len(), 2);
+
+    let edge = grid.logistics_edges.last().unwrap();
+    assert_eq!((edge.from_instance_id.as_str(), edge.to_instance_id.as_str()), (ids[0].as_str(), ids[1].as_str()));
+    assert!(!grid.is_area_clear(6, 24, 3, 3));
+}
+
+#[test]
+fn test_paste_collision_leaves_grid_untouched() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let blueprint = Blueprint::capture(&source_grid(), &geometry, 2, 2, 13, 9);
+    let mut grid = source_grid();
+    let occupancy = grid.occupancy.clone();
+
+    // The furnace half would land on "other" at (20, 20)
+    assert!(blueprint.paste(&mut grid, &geometry, 10, 19, 0).is_err());
+    assert_eq!(grid.placed_facilities.len(), 3);
+    assert_eq!(grid.logistics_edges.len(), 1);
+    assert_eq!(grid.occupancy, occupancy);
+
+    // Off the plate
+    assert!(blueprint.paste(&mut grid, &geometry, 25, 0, 0).is_err());
+    assert_eq!(grid.placed_facilities.len(), 3);
+}
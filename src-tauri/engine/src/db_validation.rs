@@ -0,0 +1,157 @@
+use crate::facility::Facility;
+use crate::geometry::Side;
+use crate::item::Item;
+use crate::recipe::Recipe;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The engine will misbehave or silently drop data
+    Error,
+    /// Suspicious but usable
+    Warning,
+}
+
+/// One problem found in database.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseIssue {
+    pub severity: Severity,
+    /// `facilities`, `items`, `recipes` or `config`
+    pub section: String,
+    /// Id of the offending entry (or its index when it has none)
+    pub id: String,
+    pub message: String,
+}
+
+impl DatabaseIssue {
+    fn new(severity: Severity, section: &str, id: &str, message: String) -> Self {
+        Self { severity, section: section.to_string(), id: id.to_string(), message }
+    }
+}
+
+/// Checks database.json for entries the loaders would drop and for broken references
+/// between facilities, items, recipes and config
+pub fn validate_database(db: &serde_json::Value) -> Vec<DatabaseIssue> {
+    let mut issues = Vec::new();
+    let facilities: Vec<Facility> = parse_section(db, "facilities", &mut issues);
+    let items: Vec<Item> = parse_section(db, "items", &mut issues);
+    let recipes: Vec<Recipe> = parse_section(db, "recipes", &mut issues);
+
+    let facility_ids = unique_ids("facilities", facilities.iter().map(|f| f.id.as_str()), &mut issues);
+    let item_ids = unique_ids("items", items.iter().map(|i| i.id.as_str()), &mut issues);
+    unique_ids("recipes", recipes.iter().map(|r| r.id.as_str()), &mut issues);
+
+    for facility in &facilities {
+        check_ports(facility, &mut issues);
+    }
+
+    for recipe in &recipes {
+        let mut error = |message: String| issues.push(DatabaseIssue::new(Severity::Error, "recipes", &recipe.id, message));
+        if !facility_ids.contains(recipe.facility_id.as_str()) {
+            error(format!("Unknown facility {}", recipe.facility_id));
+        }
+        if recipe.crafting_time <= 0.0 {
+            error(format!("Crafting time {} must be positive", recipe.crafting_time));
+        }
+        if recipe.outputs.is_empty() {
+            error("Recipe has no outputs".to_string());
+        }
+        for ingredient in recipe.inputs.iter().chain(&recipe.outputs) {
+            if !item_ids.contains(ingredient.item_id.as_str()) {
+                error(format!("Unknown item {}", ingredient.item_id));
+            }
+            if ingredient.amount <= 0.0 {
+                error(format!("Amount of {} must be positive", ingredient.item_id));
+            }
+        }
+    }
+
+    let produced: HashSet<&str> = recipes.iter()
+        .flat_map(|r| r.outputs.iter().map(|o| o.item_id.as_str()))
+        .collect();
+    for item in items.iter().filter(|i| !i.is_raw_material && !produced.contains(i.id.as_str())) {
+        issues.push(DatabaseIssue::new(Severity::Warning, "items", &item.id,
+            "Not a raw material and no recipe produces it".to_string()));
+    }
+
+    check_config(&db["config"], &facility_ids, &mut issues);
+    let no_miners = db["config"]["miner_facility_ids"].as_array().is_none_or(|ids| ids.is_empty());
+    if no_miners && items.iter().any(|i| i.is_raw_material) {
+        issues.push(DatabaseIssue::new(Severity::Warning, "config", "miner_facility_ids",
+            "Raw materials exist but no miner is configured; resource nodes can't be extracted".to_string()));
+    }
+    issues
+}
+
+/// Deserializes a section entry by entry, so one bad entry doesn't hide the rest
+fn parse_section<T: DeserializeOwned>(db: &serde_json::Value, section: &str, issues: &mut Vec<DatabaseIssue>) -> Vec<T> {
+    let Some(entries) = db[section].as_array() else {
+        issues.push(DatabaseIssue::new(Severity::Error, section, "", "Section is missing or not a list".to_string()));
+        return Vec::new();
+    };
+    entries.iter().enumerate().filter_map(|(i, entry)| {
+        serde_json::from_value(entry.clone())
+            .map_err(|e| {
+                let id = entry["id"].as_str().map(str::to_string).unwrap_or_else(|| format!("#{}", i));
+                issues.push(DatabaseIssue::new(Severity::Error, section, &id, format!("Unreadable entry: {}", e)));
+            })
+            .ok()
+    }).collect()
+}
+
+fn unique_ids<'a>(section: &str, ids: impl Iterator<Item = &'a str>, issues: &mut Vec<DatabaseIssue>) -> HashSet<&'a str> {
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !seen.insert(id) {
+            issues.push(DatabaseIssue::new(Severity::Error, section, id, "Duplicate id".to_string()));
+        }
+    }
+    seen
+}
+
+fn check_ports(facility: &Facility, issues: &mut Vec<DatabaseIssue>) {
+    let mut seen = HashSet::new();
+    for port in facility.ports.iter().flatten() {
+        let mut error = |message: String| issues.push(DatabaseIssue::new(Severity::Error, "facilities", &facility.id, message));
+        if !seen.insert(port.id.as_str()) {
+            error(format!("Duplicate port {}", port.id));
+        }
+        if port.port_type != "input" && port.port_type != "output" {
+            error(format!("Port {} has type {:?}, expected input or output", port.id, port.port_type));
+        }
+        if Side::parse(&port.direction).is_none() {
+            error(format!("Port {} has direction {:?}", port.id, port.direction));
+        }
+        if port.x >= facility.width || port.y >= facility.height {
+            error(format!("Port {} at ({}, {}) is outside the {}x{} footprint",
+                port.id, port.x, port.y, facility.width, facility.height));
+        }
+    }
+}
+
+fn check_config(config: &serde_json::Value, facility_ids: &HashSet<&str>, issues: &mut Vec<DatabaseIssue>) {
+    let mut error = |id: &str, message: String| issues.push(DatabaseIssue::new(Severity::Error, "config", id, message));
+
+    for key in ["universal_provider_facility_ids", "miner_facility_ids"] {
+        for id in config[key].as_array().into_iter().flatten() {
+            match id.as_str() {
+                Some(id) if facility_ids.contains(id) => {}
+                _ => error(key, format!("Unknown facility {}", id)),
+            }
+        }
+    }
+    for key in ["primary_provider_id", "secondary_provider_id", "belt_id"] {
+        if let Some(id) = config[key].as_str().filter(|id| !facility_ids.contains(id)) {
+            error(key, format!("Unknown facility {}", id));
+        }
+    }
+    for preset in config["presets"].as_array().into_iter().flatten() {
+        let id = preset["id"].as_str().unwrap_or("");
+        if preset["width"].as_u64().is_none_or(|w| w == 0) || preset["height"].as_u64().is_none_or(|h| h == 0) {
+            error("presets", format!("Preset {:?} needs a positive width and height", id));
+        }
+    }
+}
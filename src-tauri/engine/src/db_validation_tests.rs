@@ -0,0 +1,48 @@
+use crate::data_loader::DataLoader;
+use crate::db_validation::{validate_database, Severity};
+
+#[test]
+fn test_shipped_database_is_valid() {
+    let issues = validate_database(&DataLoader::load_database().unwrap());
+    let errors: Vec<_> = issues.iter().filter(|i| i.severity == Severity::Error).collect();
+    assert!(errors.is_empty(), "{:#?}", errors);
+}
+
+#[test]
+fn test_reports_broken_references() {
+    let mut db = DataLoader::load_database().unwrap();
+    db["recipes"][0]["facility_id"] = serde_json::json!("no_such_facility");
+    db["recipes"][1]["inputs"][0]["item_id"] = serde_json::json!("no_such_item");
+    db["recipes"][2]["time"] = serde_json::json!(0);
+    db["facilities"][0]["ports"][0]["direction"] = serde_json::json!("up");
+    db["facilities"][1]["ports"][0]["x"] = serde_json::json!(99);
+    db["items"][0] = serde_json::json!({ "id": "broken" });
+    db["config"]["belt_id"] = serde_json::json!("no_such_belt");
+    let recipes = db["recipes"].clone();
+    db["recipes"].as_array_mut().unwrap().push(recipes[3].clone());
+
+    let issues = validate_database(&db);
+    let has = |section: &str, message: &str| issues.iter()
+        .any(|i| i.section == section && i.severity == Severity::Error && i.message.contains(message));
+    assert!(has("recipes", "Unknown facility no_such_facility"));
+    assert!(has("recipes", "Unknown item no_such_item"));
+    assert!(has("recipes", "Crafting time 0"));
+    assert!(has("recipes", "Duplicate id"));
+    assert!(has("facilities", "direction \"up\""));
+    assert!(has("facilities", "outside the"));
+    assert!(has("items", "Unreadable entry"));
+    assert!(has("config", "Unknown facility no_such_belt"));
+    // The dropped item leaves whatever used it unresolved
+    assert!(issues.iter().any(|i| i.message == format!("Unknown item {}", DataLoader::load_database().unwrap()["items"][0]["id"].as_str().unwrap())));
+}
+
+#[test]
+fn test_warns_without_miners() {
+    let mut db = DataLoader::load_database().unwrap();
+    let warned = |db: &serde_json::Value| validate_database(db).iter()
+        .any(|i| i.severity == Severity::Warning && i.id == "miner_facility_ids");
+    assert!(!warned(&db));
+
+    db["config"]["miner_facility_ids"] = serde_json::json!([]);
+    assert!(warned(&db));
+}
@@ -0,0 +1,171 @@
+use crate::facility::PlacedFacility;
+use crate::geometry::{Shape, WorldPort};
+use crate::grid::GridState;
+use crate::logistics::LogisticsEdge;
+use crate::logistics_engine::LogisticsEngine;
+use crate::recipe::Recipe;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+/// Something wrong with one logistics edge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EdgeIssue {
+    /// Endpoint that is not a placed facility
+    MissingFacility { instance_id: String },
+    /// The facility type has no port with this id
+    UnknownPort { instance_id: String, port_id: String },
+    /// Edges run from an output port into an input port
+    WrongPortType { instance_id: String, port_id: String, expected: String },
+    /// The cell in front of the port is off the plate or built over, so no belt reaches it
+    PortBlocked { instance_id: String, port_id: String, x: i32, y: i32 },
+    /// Nothing the source can send is accepted by the target
+    NoFlowableItems,
+}
+
+impl EdgeIssue {
+    /// Fatal issues make the edge meaningless and are rejected; the rest are reported
+    /// because the edge is wired but won't carry anything yet.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, EdgeIssue::MissingFacility { .. } | EdgeIssue::UnknownPort { .. } | EdgeIssue::WrongPortType { .. })
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            EdgeIssue::MissingFacility { instance_id } => format!("facility {} not found", instance_id),
+            EdgeIssue::UnknownPort { instance_id, port_id } => format!("{} has no port {}", instance_id, port_id),
+            EdgeIssue::WrongPortType { instance_id, port_id, expected } => {
+                format!("port {} on {} is not an {}", port_id, instance_id, expected)
+            }
+            EdgeIssue::PortBlocked { instance_id, port_id, x, y } => {
+                format!("port {} on {} is blocked at ({}, {})", port_id, instance_id, x, y)
+            }
+            EdgeIssue::NoFlowableItems => "no item can flow from source to target".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeReport {
+    /// `LogisticsEdge::key`
+    pub edge: String,
+    pub issues: Vec<EdgeIssue>,
+    /// Item types that can travel the edge, sorted. `None` when neither end restricts it
+    /// (e.g. a belt feeding a belt).
+    pub items: Option<Vec<String>>,
+}
+
+/// Checks that both ports exist on their facility types and that the edge runs
+/// from an output into an input. Only needs the facility types, not their placement.
+pub fn port_issues(from: &PlacedFacility, to: &PlacedFacility, edge: &LogisticsEdge, geometry: &serde_json::Value) -> Vec<EdgeIssue> {
+    let mut issues = Vec::new();
+    for (facility, port_id, expected) in [(from, &edge.from_port_id, "output"), (to, &edge.to_port_id, "input")] {
+        let shape = Shape::from_geometry(geometry, &facility.facility_id);
+        match shape.as_ref().and_then(|s| s.ports.iter().find(|p| p.id == *port_id)) {
+            None => issues.push(EdgeIssue::UnknownPort {
+                instance_id: facility.instance_id.clone(),
+                port_id: port_id.clone(),
+            }),
+            Some(port) if port.port_type != expected => issues.push(EdgeIssue::WrongPortType {
+                instance_id: facility.instance_id.clone(),
+                port_id: port_id.clone(),
+                expected: expected.to_string(),
+            }),
+            Some(_) => {}
+        }
+    }
+    issues
+}
+
+fn world_port(facility: &PlacedFacility, port_id: &str, geometry: &serde_json::Value) -> Option<WorldPort> {
+    Shape::from_geometry(geometry, &facility.facility_id)?
+        .world_ports(facility.x, facility.y, facility.rotation)
+        .into_iter()
+        .find(|p| p.id == port_id)
+}
+
+/// `None` means unrestricted; two restrictions keep what they have in common
+fn restrict(a: Option<BTreeSet<String>>, b: Option<BTreeSet<String>>) -> Option<BTreeSet<String>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.intersection(&b).cloned().collect()),
+        (a, b) => a.or(b),
+    }
+}
+
+impl GridState {
+    /// Checks every edge on the grid, see `check_edge`
+    pub fn check_edges(&self, geometry: &serde_json::Value, recipes: &[Recipe]) -> Vec<EdgeReport> {
+        self.logistics_edges.iter().map(|edge| self.check_edge(edge, geometry, recipes)).collect()
+    }
+
+    /// Validates an edge against the port definitions and placement of both ends,
+    /// and works out which items can flow on it from recipes and filters
+    pub fn check_edge(&self, edge: &LogisticsEdge, geometry: &serde_json::Value, recipes: &[Recipe]) -> EdgeReport {
+        let find = |id: &str| self.placed_facilities.iter().find(|f| f.instance_id == id);
+        let mut report = EdgeReport { edge: edge.key(), issues: Vec::new(), items: None };
+
+        let (Some(from), Some(to)) = (find(&edge.from_instance_id), find(&edge.to_instance_id)) else {
+            for id in [&edge.from_instance_id, &edge.to_instance_id] {
+                if find(id).is_none() {
+                    report.issues.push(EdgeIssue::MissingFacility { instance_id: id.clone() });
+                }
+            }
+            return report;
+        };
+
+        report.issues = port_issues(from, to, edge, geometry);
+        if report.issues.is_empty() {
+            report.issues.extend(self.blocked_ports(from, to, edge, geometry));
+        }
+
+        let items = self.flowable_items(from, to, edge, recipes);
+        if items.as_ref().is_some_and(|items| items.is_empty()) {
+            report.issues.push(EdgeIssue::NoFlowableItems);
+        }
+        report.items = items.map(|items| items.into_iter().collect());
+        report
+    }
+
+    /// Ports whose outside cell can't take a belt. Two ports facing each other
+    /// across the boundary connect directly and need no free cell.
+    fn blocked_ports(&self, from: &PlacedFacility, to: &PlacedFacility, edge: &LogisticsEdge, geometry: &serde_json::Value) -> Vec<EdgeIssue> {
+        let (Some(out), Some(inp)) = (world_port(from, &edge.from_port_id, geometry), world_port(to, &edge.to_port_id, geometry)) else {
+            return Vec::new();
+        };
+        if out.outside_cell() == (inp.x, inp.y) && inp.outside_cell() == (out.x, out.y) {
+            return Vec::new();
+        }
+
+        [(from, &out), (to, &inp)].into_iter()
+            .filter_map(|(facility, port)| {
+                let (x, y) = port.outside_cell();
+                (!self.is_area_clear(x, y, 1, 1)).then(|| EdgeIssue::PortBlocked {
+                    instance_id: facility.instance_id.clone(),
+                    port_id: port.id.clone(),
+                    x,
+                    y,
+                })
+            })
+            .collect()
+    }
+
+    /// Mirrors `LogisticsEngine::transfer_items`: providers hand out only the filtered item,
+    /// producers their recipe outputs, and targets take their recipe inputs (providers and
+    /// recipe-less logistics take anything).
+    fn flowable_items(&self, from: &PlacedFacility, to: &PlacedFacility, edge: &LogisticsEdge, recipes: &[Recipe]) -> Option<BTreeSet<String>> {
+        let to_set = |items: HashSet<&str>| items.into_iter().map(str::to_string).collect::<BTreeSet<_>>();
+        let filter = LogisticsEngine::edge_filter(from, edge).map(|item| BTreeSet::from([item.to_string()]));
+
+        let sent = if self.provider_ids.contains(&from.facility_id) {
+            Some(filter.unwrap_or_default())
+        } else {
+            restrict(from.produced_items(recipes).map(to_set), filter)
+        };
+        let accepted = if self.provider_ids.contains(&to.facility_id) {
+            None
+        } else {
+            to.accepted_items(recipes).map(to_set)
+        };
+        restrict(sent, accepted)
+    }
+}
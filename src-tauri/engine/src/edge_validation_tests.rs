@@ -0,0 +1,100 @@
+use crate::data_loader::DataLoader;
+use crate::edge_validation::EdgeIssue;
+use crate::facility::{PlacedFacility, PortSetting};
+use crate::grid::{GridState, PlacementIssue};
+use crate::grid_edit::GridEdit;
+use crate::logistics::LogisticsEdge;
+
+/// PAC at (0, 0) and a furnace at (10, 0), not connected yet
+fn pac_and_furnace(geometry: &serde_json::Value) -> GridState {
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("pac", "hub_pac_main", 0, 0) }, geometry).unwrap();
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("furnace", "item_port_furnance_1", 10, 0) }, geometry).unwrap();
+    grid
+}
+
+#[test]
+fn test_rejects_edges_between_wrong_ports() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = pac_and_furnace(&geometry);
+
+    for edge in [
+        LogisticsEdge::new("pac", "in_t1", "furnace", "in_1", "item_iron_ore"),
+        LogisticsEdge::new("pac", "out_r1", "furnace", "out_4", "item_iron_ore"),
+        LogisticsEdge::new("pac", "out_r9", "furnace", "in_1", "item_iron_ore"),
+    ] {
+        assert!(grid.apply_edit(GridEdit::AddEdge { edge }, &geometry).is_err());
+    }
+    assert!(grid.logistics_edges.is_empty());
+
+    let facilities = grid.placed_facilities.clone();
+    let issues = grid.validate_layout(&facilities, &[LogisticsEdge::new("furnace", "in_1", "pac", "in_t1", "placeholder")], &geometry);
+    assert_eq!(issues, vec![PlacementIssue::InvalidEdge {
+        edge: "furnace:in_1->pac:in_t1".to_string(),
+        issue: EdgeIssue::WrongPortType {
+            instance_id: "furnace".to_string(),
+            port_id: "in_1".to_string(),
+            expected: "output".to_string(),
+        },
+    }]);
+}
+
+#[test]
+fn test_reports_items_that_can_flow() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let recipes = DataLoader::load_recipes().unwrap();
+    let mut grid = pac_and_furnace(&geometry);
+    let items = |grid: &GridState, edge: &LogisticsEdge| grid.check_edge(edge, &geometry, &recipes).items;
+
+    let ore = LogisticsEdge::new("pac", "out_r1", "furnace", "in_1", "item_iron_ore");
+    let report = grid.check_edge(&ore, &geometry, &recipes);
+    assert!(report.issues.is_empty());
+    assert_eq!(report.items, Some(vec!["item_iron_ore".to_string()]));
+
+    // Anything the furnace can make goes back into the PAC, until it's locked to one recipe
+    let back = LogisticsEdge::new("furnace", "out_4", "pac", "in_t1", "placeholder");
+    let all = items(&grid, &back).unwrap();
+    assert!(all.contains(&"item_iron_nugget".to_string()) && all.contains(&"item_carbon_mtl".to_string()));
+    grid.placed_facilities[1].locked_recipe_id = Some("furnance_iron_nugget_1".to_string());
+    assert_eq!(items(&grid, &back), Some(vec!["item_iron_nugget".to_string()]));
+
+    // The locked furnace no longer takes powder, and the source port setting beats the edge item
+    grid.placed_facilities[0].port_settings = Some(vec![PortSetting {
+        port_id: "out_r1".to_string(),
+        item_id: "item_iron_powder".to_string(),
+    }]);
+    let report = grid.check_edge(&ore, &geometry, &recipes);
+    assert_eq!(report.issues, vec![EdgeIssue::NoFlowableItems]);
+    grid.placed_facilities[1].locked_recipe_id = None;
+    assert_eq!(items(&grid, &ore), Some(vec!["item_iron_powder".to_string()]));
+
+    // A PAC port with no item configured sends nothing
+    let unset = LogisticsEdge::new("pac", "out_r2", "furnace", "in_2", "placeholder");
+    assert_eq!(grid.check_edge(&unset, &geometry, &recipes).issues, vec![EdgeIssue::NoFlowableItems]);
+}
+
+#[test]
+fn test_flags_blocked_ports() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let recipes = DataLoader::load_recipes().unwrap();
+    let mut grid = pac_and_furnace(&geometry);
+
+    // The furnace's inputs face right; a second furnace built flush against them blocks in_1
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("wall", "item_port_furnance_1", 13, 0) }, &geometry).unwrap();
+    let edge = LogisticsEdge::new("pac", "out_r1", "furnace", "in_1", "item_iron_ore");
+    grid.apply_edit(GridEdit::AddEdge { edge: edge.clone() }, &geometry).unwrap();
+
+    let report = grid.check_edge(&edge, &geometry, &recipes);
+    assert_eq!(report.issues, vec![EdgeIssue::PortBlocked {
+        instance_id: "furnace".to_string(),
+        port_id: "in_1".to_string(),
+        x: 13,
+        y: 0,
+    }]);
+    assert!(!report.issues[0].is_fatal());
+
+    // ...unless that furnace's output port sits right in front of it, which connects directly
+    let direct = LogisticsEdge::new("wall", "out_4", "furnace", "in_1", "placeholder");
+    assert!(grid.check_edge(&direct, &geometry, &recipes).issues.is_empty());
+    assert_eq!(grid.check_edges(&geometry, &recipes).len(), 1);
+}
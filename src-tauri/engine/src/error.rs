@@ -0,0 +1,173 @@
+use crate::grid::PlacementIssue;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Errors the engine hands to its front ends. Serializes as
+/// `{ "code": "missing_facility", "message": "...", "context": { "id": "..." } }`
+/// so the UI can match on `code` and pull details out of `context`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    /// No placed facility with this instance id, or no facility type with this id
+    MissingFacility { id: String },
+    MissingRecipe { recipe_id: String },
+    /// No logistics edge with this key
+    MissingEdge { key: String },
+    /// No plate link with this key
+    MissingLink { key: String },
+    MissingPlate { name: String },
+    /// No plate size preset with this id or name in config
+    MissingPreset { preset: String },
+    /// A facility, edge, plate or link with this id is already there; `kind` says which
+    Duplicate { kind: &'static str, id: String },
+    /// The facility would overlap another, leave the plate or cover blocked terrain at (x, y)
+    Collision { facility_id: String, x: i32, y: i32 },
+    /// The edge can't be laid between its ports
+    RejectedEdge { key: String, reason: String },
+    /// Input slot `slot_index` won't take the item
+    RejectedItem { instance_id: String, slot_index: usize, item_id: String, reason: String },
+    /// The recipe runs on a different facility type
+    RecipeMismatch { recipe_id: String, facility_id: String },
+    /// Slot index past the facility's input slots
+    InvalidSlot { instance_id: String, slot_index: usize, slot_count: usize },
+    /// The solver can't produce a plan for the request; `item_id` names the target at fault, if any
+    InfeasiblePlan { item_id: Option<String>, reason: String },
+    /// A full layout sync failed validation; nothing was applied
+    RejectedLayout { issues: Vec<PlacementIssue> },
+    /// database.json is missing, unreadable or malformed
+    Database { path: Option<String>, reason: String },
+    /// Any other rejected operation (bad rotations, bad files, ...)
+    Invalid { reason: String },
+}
+
+impl EngineError {
+    pub fn missing_facility(id: impl Into<String>) -> Self {
+        Self::MissingFacility { id: id.into() }
+    }
+
+    pub fn invalid(reason: impl Into<String>) -> Self {
+        Self::Invalid { reason: reason.into() }
+    }
+
+    pub fn infeasible(reason: impl Into<String>) -> Self {
+        Self::InfeasiblePlan { item_id: None, reason: reason.into() }
+    }
+
+    pub fn database(path: Option<&str>, reason: impl fmt::Display) -> Self {
+        Self::Database { path: path.map(str::to_string), reason: reason.to_string() }
+    }
+
+    /// Stable identifier for the variant
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingFacility { .. } => "missing_facility",
+            Self::MissingRecipe { .. } => "missing_recipe",
+            Self::MissingEdge { .. } => "missing_edge",
+            Self::MissingLink { .. } => "missing_link",
+            Self::MissingPlate { .. } => "missing_plate",
+            Self::MissingPreset { .. } => "missing_preset",
+            Self::Duplicate { .. } => "duplicate",
+            Self::Collision { .. } => "collision",
+            Self::RejectedEdge { .. } => "rejected_edge",
+            Self::RejectedItem { .. } => "rejected_item",
+            Self::RecipeMismatch { .. } => "recipe_mismatch",
+            Self::InvalidSlot { .. } => "invalid_slot",
+            Self::InfeasiblePlan { .. } => "infeasible_plan",
+            Self::RejectedLayout { .. } => "rejected_layout",
+            Self::Database { .. } => "database_error",
+            Self::Invalid { .. } => "invalid",
+        }
+    }
+
+    /// The variant's fields as a JSON object
+    pub fn context(&self) -> serde_json::Value {
+        match self {
+            Self::MissingFacility { id } => serde_json::json!({ "id": id }),
+            Self::MissingRecipe { recipe_id } => serde_json::json!({ "recipe_id": recipe_id }),
+            Self::MissingEdge { key } | Self::MissingLink { key } => serde_json::json!({ "key": key }),
+            Self::MissingPlate { name } => serde_json::json!({ "name": name }),
+            Self::MissingPreset { preset } => serde_json::json!({ "preset": preset }),
+            Self::Duplicate { kind, id } => serde_json::json!({ "kind": kind, "id": id }),
+            Self::Collision { facility_id, x, y } => serde_json::json!({ "facility_id": facility_id, "x": x, "y": y }),
+            Self::RejectedEdge { key, reason } => serde_json::json!({ "key": key, "reason": reason }),
+            Self::RejectedItem { instance_id, slot_index, item_id, reason } => serde_json::json!({
+                "instance_id": instance_id,
+                "slot_index": slot_index,
+                "item_id": item_id,
+                "reason": reason,
+            }),
+            Self::RecipeMismatch { recipe_id, facility_id } => serde_json::json!({ "recipe_id": recipe_id, "facility_id": facility_id }),
+            Self::InvalidSlot { instance_id, slot_index, slot_count } => serde_json::json!({
+                "instance_id": instance_id,
+                "slot_index": slot_index,
+                "slot_count": slot_count,
+            }),
+            Self::InfeasiblePlan { item_id, reason } => serde_json::json!({ "item_id": item_id, "reason": reason }),
+            Self::RejectedLayout { issues } => serde_json::json!({ "issues": issues }),
+            Self::Database { path, reason } => serde_json::json!({ "path": path, "reason": reason }),
+            Self::Invalid { reason } => serde_json::json!({ "reason": reason }),
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingFacility { id } => write!(f, "Facility {} not found", id),
+            Self::MissingRecipe { recipe_id } => write!(f, "Recipe {} not found", recipe_id),
+            Self::MissingEdge { key } => write!(f, "Edge {} not found", key),
+            Self::MissingLink { key } => write!(f, "Link {} not found", key),
+            Self::MissingPlate { name } => write!(f, "Plate {} not found", name),
+            Self::MissingPreset { preset } => write!(f, "Preset {} not found", preset),
+            Self::Duplicate { kind, id } => write!(f, "Duplicate {} {}", kind, id),
+            Self::Collision { facility_id, x, y } => {
+                write!(f, "{} would collide, leave the plate or cover blocked terrain at ({}, {})", facility_id, x, y)
+            }
+            Self::RejectedEdge { key, reason } => write!(f, "Edge {}: {}", key, reason),
+            Self::RejectedItem { instance_id, slot_index, item_id, reason } => {
+                write!(f, "Slot {} of {} won't take {}: {}", slot_index, instance_id, item_id, reason)
+            }
+            Self::RecipeMismatch { recipe_id, facility_id } => write!(f, "Recipe {} does not run on {}", recipe_id, facility_id),
+            Self::InvalidSlot { instance_id, slot_index, slot_count } => {
+                write!(f, "Slot {} out of range, {} has {} input slots", slot_index, instance_id, slot_count)
+            }
+            Self::InfeasiblePlan { item_id: Some(item_id), reason } => write!(f, "Cannot plan {}: {}", item_id, reason),
+            Self::InfeasiblePlan { item_id: None, reason } => write!(f, "Cannot plan production: {}", reason),
+            Self::RejectedLayout { issues } => write!(f, "Layout rejected with {} issues", issues.len()),
+            Self::Database { path: Some(path), reason } => write!(f, "Database {}: {}", path, reason),
+            Self::Database { path: None, reason } => write!(f, "Database: {}", reason),
+            Self::Invalid { reason } => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// Modules that still report plain messages end up as `Invalid`
+impl From<String> for EngineError {
+    fn from(reason: String) -> Self {
+        Self::Invalid { reason }
+    }
+}
+
+impl From<&str> for EngineError {
+    fn from(reason: &str) -> Self {
+        Self::Invalid { reason: reason.to_string() }
+    }
+}
+
+impl From<EngineError> for String {
+    fn from(error: EngineError) -> Self {
+        error.to_string()
+    }
+}
+
+impl Serialize for EngineError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("EngineError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("context", &self.context())?;
+        state.end()
+    }
+}
@@ -0,0 +1,61 @@
+use crate::data_loader::DataLoader;
+use crate::error::EngineError;
+use crate::facility::PlacedFacility;
+use crate::grid::GridState;
+use crate::grid_edit::GridEdit;
+use crate::rate::Rate;
+use crate::recipe_solver::RecipeSolver;
+
+#[test]
+fn test_serializes_code_message_and_context() {
+    let error = EngineError::InvalidSlot { instance_id: "furnace_0".to_string(), slot_index: 3, slot_count: 2 };
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["code"], "invalid_slot");
+    assert_eq!(json["message"], "Slot 3 out of range, furnace_0 has 2 input slots");
+    assert_eq!(json["context"], serde_json::json!({ "instance_id": "furnace_0", "slot_index": 3, "slot_count": 2 }));
+
+    let json = serde_json::to_value(EngineError::Duplicate { kind: "plate", id: "Mine".to_string() }).unwrap();
+    assert_eq!(json["code"], "duplicate");
+    assert_eq!(json["context"], serde_json::json!({ "kind": "plate", "id": "Mine" }));
+
+    // Plain messages from modules without their own variants
+    let json = serde_json::to_value(EngineError::invalid("Plate name must not be empty")).unwrap();
+    assert_eq!(json["code"], "invalid");
+    assert_eq!(json["context"]["reason"], "Plate name must not be empty");
+}
+
+#[test]
+fn test_solver_reports_infeasible_requests() {
+    let solver = RecipeSolver::from_database().unwrap();
+
+    let error = solver.solve(vec![("item_iron_nugget".to_string(), Rate::per_minute(-5.0))], 32, 32).unwrap_err();
+    assert!(matches!(error, EngineError::InfeasiblePlan { item_id: Some(ref id), .. } if id == "item_iron_nugget"));
+    assert!(matches!(solver.solve(Vec::new(), 32, 32), Err(EngineError::InfeasiblePlan { item_id: None, .. })));
+    assert_eq!(solver.solve(vec![("item_iron_nugget".to_string(), Rate::per_minute(30.0))], 0, 32).unwrap_err().code(), "infeasible_plan");
+}
+
+#[test]
+fn test_grid_edits_name_missing_facilities() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+
+    let error = grid.apply_edit(GridEdit::RemoveFacility { instance_id: "ghost_0".to_string() }, &geometry).unwrap_err();
+    assert_eq!(error, EngineError::missing_facility("ghost_0"));
+    let error = grid.apply_edit(GridEdit::RotateFacility { instance_id: "ghost_0".to_string(), rotation: 90 }, &geometry).unwrap_err();
+    assert_eq!(error.code(), "missing_facility");
+}
+
+#[test]
+fn test_grid_edits_report_collisions_with_position() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("furnace", "item_port_furnance_1", 0, 0) }, &geometry).unwrap();
+
+    let again = GridEdit::PlaceFacility { facility: PlacedFacility::new("furnace", "item_port_furnance_1", 10, 10) };
+    assert_eq!(grid.apply_edit(again, &geometry).unwrap_err(), EngineError::Duplicate { kind: "facility", id: "furnace".to_string() });
+
+    let overlap = GridEdit::PlaceFacility { facility: PlacedFacility::new("", "item_port_furnance_1", 1, 2) };
+    let error = grid.apply_edit(overlap, &geometry).unwrap_err();
+    assert_eq!(error, EngineError::Collision { facility_id: "item_port_furnance_1".to_string(), x: 1, y: 2 });
+    assert_eq!(error.context()["x"], 1);
+}
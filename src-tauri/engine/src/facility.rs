@@ -0,0 +1,164 @@
+use crate::recipe::{Recipe, RecipeIngredient};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Port {
+    pub id: String,
+    pub x: u32,
+    pub y: u32,
+    #[serde(rename = "type")]
+    pub port_type: String, // "input" or "output"
+    pub direction: String, // "left", "right", "top", "bottom"
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Facility {
+    pub id: String,
+    pub name: String,
+    pub width: u32,  // Ukuran dalam grid (misal: 8)
+    pub height: u32, // Ukuran dalam grid (misal: 8)
+    #[serde(rename = "power")]
+    pub power_consumption: f32,
+    #[serde(default)]
+    pub tier: u32,
+    pub icon: Option<String>,
+    pub category: Option<String>,
+    pub ports: Option<Vec<Port>>,
+    pub input_slots: Option<u32>,
+    pub output_slots: Option<u32>,
+    pub throughput_limit: Option<f32>,
+    pub is_filter: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PortSetting {
+    pub port_id: String,
+    pub item_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BufferSlot {
+    pub item_id: String,
+    pub source_port_id: Option<String>,
+    pub target_port_id: Option<String>,
+    pub quantity: u32,
+}
+
+impl BufferSlot {
+    pub fn empty() -> Self {
+        Self {
+            item_id: "".to_string(),
+            source_port_id: None,
+            target_port_id: None,
+            quantity: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quantity == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::empty();
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlacedFacility {
+    pub instance_id: String,
+    pub facility_id: String,
+    pub x: i32,
+    pub y: i32,
+    pub rotation: u32, // 0, 90, 180, 270
+    pub port_settings: Option<Vec<PortSetting>>,
+    #[serde(default)]
+    pub input_buffer: Vec<BufferSlot>,
+    #[serde(default)]
+    pub output_buffer: Vec<BufferSlot>,
+    #[serde(default)]
+    pub active_recipe_id: Option<String>,
+    /// Recipe this facility is pinned to; `None` auto-detects from the input buffer
+    #[serde(default)]
+    pub locked_recipe_id: Option<String>,
+    /// Fraction of the active craft completed, 0..1
+    #[serde(default)]
+    pub recipe_progress: f64,
+    /// Seconds left on the active craft
+    #[serde(default)]
+    pub recipe_remaining: f64,
+    /// Ingredient already paid for beyond what past crafts used, in `AMOUNT_SCALE` units
+    #[serde(default)]
+    pub input_credit: HashMap<String, u64>,
+    /// Produced fractions not yet making up a whole item, in `AMOUNT_SCALE` units
+    #[serde(default)]
+    pub output_carry: HashMap<String, u64>,
+}
+
+impl PlacedFacility {
+    /// Freshly placed facility: unrotated, default ports, empty buffers and no recipe state
+    pub fn new(instance_id: &str, facility_id: &str, x: i32, y: i32) -> Self {
+        Self {
+            instance_id: instance_id.to_string(),
+            facility_id: facility_id.to_string(),
+            x,
+            y,
+            rotation: 0,
+            port_settings: None,
+            input_buffer: Vec::new(),
+            output_buffer: Vec::new(),
+            active_recipe_id: None,
+            locked_recipe_id: None,
+            recipe_progress: 0.0,
+            recipe_remaining: 0.0,
+            input_credit: HashMap::new(),
+            output_carry: HashMap::new(),
+        }
+    }
+
+    /// Whether this facility may run `recipe` (right facility type and not locked to another recipe)
+    pub fn can_run(&self, recipe: &Recipe) -> bool {
+        recipe.facility_id == self.facility_id
+            && self.locked_recipe_id.as_ref().is_none_or(|id| *id == recipe.id)
+    }
+
+    /// Ingredients of the recipes this facility may run. `None` for facility types
+    /// without recipes (belts, splitters, ...), which take anything.
+    pub fn accepted_items<'a>(&self, recipes: &'a [Recipe]) -> Option<HashSet<&'a str>> {
+        self.recipe_items(recipes, |r| &r.inputs)
+    }
+
+    /// Products of the recipes this facility may run; `None` like `accepted_items`
+    pub fn produced_items<'a>(&self, recipes: &'a [Recipe]) -> Option<HashSet<&'a str>> {
+        self.recipe_items(recipes, |r| &r.outputs)
+    }
+
+    /// Items each input slot takes: a recipe's n-th ingredient goes in slot n, for every
+    /// recipe this facility may run. `None` like `accepted_items`.
+    pub fn slot_accepted_items<'a>(&self, recipes: &'a [Recipe]) -> Option<Vec<HashSet<&'a str>>> {
+        if !self.has_recipes(recipes) {
+            return None;
+        }
+        let mut slots = vec![HashSet::new(); self.input_buffer.len()];
+        for recipe in recipes.iter().filter(|r| self.can_run(r)) {
+            for (slot, input) in slots.iter_mut().zip(&recipe.inputs) {
+                slot.insert(input.item_id.as_str());
+            }
+        }
+        Some(slots)
+    }
+
+    fn has_recipes(&self, recipes: &[Recipe]) -> bool {
+        recipes.iter().any(|r| r.facility_id == self.facility_id)
+    }
+
+    fn recipe_items<'a>(&self, recipes: &'a [Recipe], side: fn(&Recipe) -> &[RecipeIngredient]) -> Option<HashSet<&'a str>> {
+        if !self.has_recipes(recipes) {
+            return None;
+        }
+        Some(recipes.iter()
+            .filter(|r| self.can_run(r))
+            .flat_map(|r| side(r).iter().map(|i| i.item_id.as_str()))
+            .collect())
+    }
+}
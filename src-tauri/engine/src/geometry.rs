@@ -0,0 +1,161 @@
+use crate::facility::Port;
+use serde::{Deserialize, Serialize};
+
+// Rotations are clockwise quarter turns, matching the frontend: a local cell (x, y)
+// of a w x h facility ends up at (h - 1 - y, x) after 90 degrees.
+
+/// Edge of a facility, listed clockwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Top, Side::Right, Side::Bottom, Side::Left];
+
+    pub fn parse(name: &str) -> Option<Side> {
+        match name {
+            "top" => Some(Side::Top),
+            "right" => Some(Side::Right),
+            "bottom" => Some(Side::Bottom),
+            "left" => Some(Side::Left),
+            _ => None,
+        }
+    }
+
+    pub fn rotated(self, rotation: u32) -> Side {
+        Self::ALL[(self as usize + quarter_turns(rotation)) % 4]
+    }
+
+    pub fn opposite(self) -> Side {
+        self.rotated(180)
+    }
+
+    /// Step to the neighbouring cell on this side
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Side::Top => (0, -1),
+            Side::Right => (1, 0),
+            Side::Bottom => (0, 1),
+            Side::Left => (-1, 0),
+        }
+    }
+}
+
+/// A port placed on the grid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldPort {
+    pub id: String,
+    pub port_type: String,
+    pub x: i32,
+    pub y: i32,
+    /// Side of the facility the port opens to
+    pub facing: Side,
+}
+
+impl WorldPort {
+    pub fn is_input(&self) -> bool {
+        self.port_type == "input"
+    }
+
+    /// The cell just outside the port, where a belt has to connect
+    pub fn outside_cell(&self) -> (i32, i32) {
+        let (dx, dy) = self.facing.offset();
+        (self.x + dx, self.y + dy)
+    }
+}
+
+/// Size and ports of a facility type, unrotated
+#[derive(Debug, Clone)]
+pub struct Shape {
+    pub width: u32,
+    pub height: u32,
+    pub ports: Vec<Port>,
+}
+
+impl Shape {
+    /// Looks the facility up by id in the geometry (facility) array
+    pub fn from_geometry(geometry: &serde_json::Value, facility_id: &str) -> Option<Shape> {
+        let meta = geometry.as_array()?.iter().find(|f| f["id"].as_str() == Some(facility_id))?;
+        Some(Shape {
+            width: meta["width"].as_u64()? as u32,
+            height: meta["height"].as_u64()? as u32,
+            ports: serde_json::from_value(meta["ports"].clone()).unwrap_or_default(),
+        })
+    }
+
+    /// Unrotated side a port opens to. The database isn't consistent about `direction`:
+    /// machines store the way items flow (an input on the left edge says "right"), while
+    /// the PAC and 1x1 logistics pieces name the edge itself. Both agree once the port's
+    /// position is taken into account, so prefer whichever of the two sides it sits on.
+    pub fn port_facing(&self, port: &Port) -> Side {
+        let named = Side::parse(&port.direction).unwrap_or(Side::Top);
+        let on_edge = |side: Side| match side {
+            Side::Top => port.y == 0,
+            Side::Right => port.x + 1 == self.width,
+            Side::Bottom => port.y + 1 == self.height,
+            Side::Left => port.x == 0,
+        };
+        // Off both edges: fall back to reading it as a flow direction
+        if !on_edge(named) && (on_edge(named.opposite()) || port.port_type == "input") {
+            named.opposite()
+        } else {
+            named
+        }
+    }
+
+    pub fn footprint(&self, rotation: u32) -> (u32, u32) {
+        rotated_size(self.width, self.height, rotation)
+    }
+
+    /// Local cell after rotation, still relative to the facility's top-left corner
+    pub fn rotate_cell(&self, x: i32, y: i32, rotation: u32) -> (i32, i32) {
+        let (mut x, mut y) = (x, y);
+        let (mut w, mut h) = (self.width as i32, self.height as i32);
+        for _ in 0..quarter_turns(rotation) {
+            (x, y) = (h - 1 - y, x);
+            (w, h) = (h, w);
+        }
+        (x, y)
+    }
+
+    /// Every grid cell covered by the facility placed at (x, y)
+    pub fn cells(&self, x: i32, y: i32, rotation: u32) -> Vec<(i32, i32)> {
+        let (w, h) = self.footprint(rotation);
+        (0..h as i32).flat_map(|dy| (0..w as i32).map(move |dx| (x + dx, y + dy))).collect()
+    }
+
+    /// World position and facing of every port for the facility placed at (x, y)
+    pub fn world_ports(&self, x: i32, y: i32, rotation: u32) -> Vec<WorldPort> {
+        self.ports.iter().map(|port| {
+            let (px, py) = self.rotate_cell(port.x as i32, port.y as i32, rotation);
+            WorldPort {
+                id: port.id.clone(),
+                port_type: port.port_type.clone(),
+                x: x + px,
+                y: y + py,
+                facing: self.port_facing(port).rotated(rotation),
+            }
+        }).collect()
+    }
+}
+
+/// Width and height after rotation
+pub fn rotated_size(width: u32, height: u32, rotation: u32) -> (u32, u32) {
+    if quarter_turns(rotation).is_multiple_of(2) { (width, height) } else { (height, width) }
+}
+
+/// Footprint of `facility_id` from the geometry array (1x1 when unknown)
+pub fn footprint(geometry: &serde_json::Value, facility_id: &str, rotation: u32) -> (u32, u32) {
+    Shape::from_geometry(geometry, facility_id)
+        .map(|shape| shape.footprint(rotation))
+        .unwrap_or((1, 1))
+}
+
+fn quarter_turns(rotation: u32) -> usize {
+    ((rotation / 90) % 4) as usize
+}
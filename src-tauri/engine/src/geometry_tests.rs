@@ -0,0 +1,136 @@
+use crate::data_loader::DataLoader;
+use crate::facility::Port;
+use crate::geometry::{self, Shape, Side};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+
+const ROTATIONS: [u32; 4] = [0, 90, 180, 270];
+
+/// Every facility in the database plus random shapes with ports on random edges
+fn shapes() -> Vec<Shape> {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut shapes: Vec<Shape> = geometry.as_array().unwrap().iter()
+        .filter_map(|f| Shape::from_geometry(&geometry, f["id"].as_str()?))
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(36);
+    for _ in 0..200 {
+        let (width, height) = (rng.gen_range(1..10u32), rng.gen_range(1..10u32));
+        let ports = (0..rng.gen_range(0..6)).map(|i| {
+            let side = Side::ALL[rng.gen_range(0..4)];
+            let (x, y) = match side {
+                Side::Top => (rng.gen_range(0..width), 0),
+                Side::Bottom => (rng.gen_range(0..width), height - 1),
+                Side::Left => (0, rng.gen_range(0..height)),
+                Side::Right => (width - 1, rng.gen_range(0..height)),
+            };
+            let port_type = if rng.gen_bool(0.5) { "input" } else { "output" };
+            // Store the flow direction the way the database does
+            let flow = if port_type == "input" { side.opposite() } else { side };
+            Port {
+                id: format!("p{}", i),
+                x,
+                y,
+                port_type: port_type.to_string(),
+                direction: format!("{:?}", flow).to_lowercase(),
+            }
+        }).collect();
+        shapes.push(Shape { width, height, ports });
+    }
+    shapes
+}
+
+fn on_side(shape: &Shape, rotation: u32, x: i32, y: i32, side: Side) -> bool {
+    let (w, h) = shape.footprint(rotation);
+    match side {
+        Side::Top => y == 0,
+        Side::Bottom => y == h as i32 - 1,
+        Side::Left => x == 0,
+        Side::Right => x == w as i32 - 1,
+    }
+}
+
+#[test]
+fn test_footprint_and_cells_are_consistent() {
+    for shape in shapes() {
+        for rotation in ROTATIONS {
+            let (w, h) = shape.footprint(rotation);
+            assert_eq!(w * h, shape.width * shape.height);
+            assert_eq!((w, h), shape.footprint(rotation + 180));
+            assert_eq!((w, h), geometry::rotated_size(shape.width, shape.height, rotation + 360));
+
+            let cells = shape.cells(5, 7, rotation);
+            let unique: HashSet<_> = cells.iter().collect();
+            assert_eq!(unique.len() as u32, w * h);
+            assert!(cells.iter().all(|&(x, y)| x >= 5 && y >= 7 && x < 5 + w as i32 && y < 7 + h as i32));
+        }
+    }
+}
+
+#[test]
+fn test_rotating_a_full_turn_is_identity() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for shape in shapes() {
+        for _ in 0..10 {
+            let (x, y) = (rng.gen_range(0..shape.width) as i32, rng.gen_range(0..shape.height) as i32);
+            assert_eq!(shape.rotate_cell(x, y, 360), (x, y));
+
+            // Four quarter turns, each relative to the previous footprint
+            let (mut cx, mut cy) = (x, y);
+            let mut current = shape.clone();
+            for _ in 0..4 {
+                (cx, cy) = current.rotate_cell(cx, cy, 90);
+                (current.width, current.height) = current.footprint(90);
+            }
+            assert_eq!((cx, cy), (x, y));
+
+            // Rotated cells stay inside the rotated footprint
+            for rotation in ROTATIONS {
+                let (w, h) = shape.footprint(rotation);
+                let (rx, ry) = shape.rotate_cell(x, y, rotation);
+                assert!(rx >= 0 && ry >= 0 && rx < w as i32 && ry < h as i32);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_world_ports_open_outward() {
+    for shape in shapes() {
+        for rotation in ROTATIONS {
+            let cells: HashSet<_> = shape.cells(3, 4, rotation).into_iter().collect();
+            for (port, world) in shape.ports.iter().zip(shape.world_ports(3, 4, rotation)) {
+                assert_eq!(world.facing, shape.port_facing(port).rotated(rotation));
+                assert!(cells.contains(&(world.x, world.y)), "{} lies outside its facility", world.id);
+                assert!(on_side(&shape, rotation, world.x - 3, world.y - 4, world.facing), "{} is not on its facing edge", world.id);
+                assert!(!cells.contains(&world.outside_cell()));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_port_facing_reads_database_directions() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let facing = |facility_id: &str, port_id: &str| {
+        let shape = Shape::from_geometry(&geometry, facility_id).unwrap();
+        let port = shape.ports.iter().find(|p| p.id == port_id).unwrap();
+        shape.port_facing(port)
+    };
+    // Machines store the flow direction, the PAC and belts name the edge
+    assert_eq!(facing("item_port_furnance_1", "in_1"), Side::Right);
+    assert_eq!(facing("item_port_furnance_1", "out_4"), Side::Left);
+    assert_eq!(facing("hub_pac_main", "in_t1"), Side::Top);
+    assert_eq!(facing("item_port_log_belt_01", "in_1"), Side::Left);
+    assert_eq!(facing("item_port_log_belt_01", "out_1"), Side::Right);
+    assert_eq!(Side::Top.rotated(90), Side::Right);
+    assert_eq!(Side::Left.rotated(90), Side::Top);
+
+    // Winder is 6x4; at 90 degrees it becomes 4x6 and its top-left cell moves to the top-right
+    let winder = Shape::from_geometry(&geometry, "item_port_winder_1").unwrap();
+    assert_eq!(winder.footprint(90), (4, 6));
+    assert_eq!(winder.rotate_cell(0, 0, 90), (3, 0));
+    assert_eq!(winder.rotate_cell(0, 0, 180), (5, 3));
+    assert_eq!(geometry::footprint(&geometry, "no_such_facility", 90), (1, 1));
+}
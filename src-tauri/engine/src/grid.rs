@@ -0,0 +1,322 @@
+use crate::facility::{BufferSlot, PlacedFacility, Facility};
+use crate::edge_validation::{self, EdgeIssue};
+use crate::geometry;
+use crate::logistics::LogisticsEdge;
+use crate::power_grid::PowerGrid;
+use crate::simulation_stats::SimulationStats;
+use crate::terrain::Terrain;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Stack cap per buffer slot when the config has no `slot_capacity`
+const DEFAULT_SLOT_CAPACITY: u32 = 50;
+/// Used when the config doesn't list `universal_provider_facility_ids`
+const DEFAULT_PROVIDER_IDS: [&str; 2] = ["hub_pac_main", "logistics_depot_unloader"];
+/// Items per second a miner extracts when the config has no `miner_extraction_rate_per_s`
+const DEFAULT_EXTRACTION_RATE: f64 = 0.5;
+
+/// Why a layout sent to the backend was rejected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlacementIssue {
+    /// Two facilities claim the same cell (first overlapping cell)
+    Collision { instance_id: String, other_instance_id: String, x: i32, y: i32 },
+    /// Footprint reaches past the plate edge
+    OutOfBounds { instance_id: String, x: i32, y: i32, width: u32, height: u32 },
+    /// Footprint covers a blocked terrain cell (first such cell)
+    BlockedTerrain { instance_id: String, x: i32, y: i32 },
+    /// Edge endpoint that is not a placed facility
+    DanglingEdge { edge: String, missing_instance_id: String },
+    /// Edge whose ports don't exist or point the wrong way (fatal `EdgeIssue`s only)
+    InvalidEdge { edge: String, issue: EdgeIssue },
+    DuplicateInstance { instance_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridState {
+    pub width: u32,
+    pub height: u32,
+    pub placed_facilities: Vec<PlacedFacility>,
+    pub logistics_edges: Vec<LogisticsEdge>,
+    #[serde(default)]
+    pub terrain: Terrain,
+    #[serde(skip)]
+    pub occupancy: Vec<bool>, // Fast lookup for occupancy
+    #[serde(skip)]
+    pub power_grid: PowerGrid,
+    #[serde(skip)]
+    pub grid_size: u32,
+    /// Simulation clock in seconds, advanced by `LogisticsEngine::step`
+    #[serde(skip)]
+    pub sim_time: f64,
+    /// Fractional transfer credit per edge (keyed by `LogisticsEdge::key`)
+    #[serde(skip)]
+    pub edge_progress: HashMap<String, f64>,
+    #[serde(skip)]
+    pub stats: SimulationStats,
+    /// Facility types that supply raw materials without limit and absorb deliveries (PAC, Depot Unloader)
+    #[serde(skip)]
+    pub provider_ids: HashSet<String>,
+    /// Max stack per buffer slot (config `slot_capacity`)
+    #[serde(skip)]
+    pub slot_capacity: u32,
+    /// Facility types that extract the resource node they cover (config `miner_facility_ids`)
+    #[serde(skip)]
+    pub miner_ids: HashSet<String>,
+    /// Items per second each miner extracts (config `miner_extraction_rate_per_s`)
+    #[serde(skip)]
+    pub extraction_rate: f64,
+    /// Item each placed miner extracts, by instance id. Miners off any node aren't listed.
+    #[serde(skip)]
+    pub mined_items: HashMap<String, String>,
+}
+
+impl GridState {
+    pub fn new(config: &serde_json::Value) -> Self {
+        let width = config["simulation_constants"]["default_plate_width"].as_u64().unwrap_or(32) as u32;
+        let height = config["simulation_constants"]["default_plate_height"].as_u64().unwrap_or(32) as u32;
+        Self::with_size(config, width, height)
+    }
+
+    pub fn with_size(config: &serde_json::Value, width: u32, height: u32) -> Self {
+        let provider_ids: HashSet<String> = config["universal_provider_facility_ids"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_else(|| DEFAULT_PROVIDER_IDS.iter().map(|s| s.to_string()).collect());
+        let slot_capacity = config["slot_capacity"].as_u64().unwrap_or(DEFAULT_SLOT_CAPACITY as u64) as u32;
+        let miner_ids: HashSet<String> = config["miner_facility_ids"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+        let extraction_rate = config["miner_extraction_rate_per_s"].as_f64().unwrap_or(DEFAULT_EXTRACTION_RATE);
+        // Grid size is implicitly 1x1 block in this logic
+        let grid_size = 1; 
+        
+        Self {
+            width,
+            height,
+            placed_facilities: Vec::new(),
+            logistics_edges: Vec::new(),
+            terrain: Terrain::default(),
+            occupancy: vec![false; (width * height) as usize],
+            power_grid: PowerGrid::new(),
+            grid_size,
+            sim_time: 0.0,
+            edge_progress: HashMap::new(),
+            stats: SimulationStats::default(),
+            provider_ids,
+            slot_capacity,
+            miner_ids,
+            extraction_rate,
+            mined_items: HashMap::new(),
+        }
+    }
+
+    pub fn update_power_grid(&mut self, geometry: &serde_json::Value) {
+        self.power_grid.calculate(&self.placed_facilities, geometry, self.grid_size);
+    }
+
+    /// Gives every placed facility exactly `input_slots` / `output_slots` buffer slots
+    /// (at least one each, so belts and other slot-less logistics can hold a stack).
+    /// Buffers of the wrong length are compacted; stacks beyond the slot count are dropped.
+    pub fn resize_buffers(&mut self, geometry: &serde_json::Value) {
+        let empty_vec = vec![];
+        let geom_array = geometry.as_array().unwrap_or(&empty_vec);
+
+        for facility in &mut self.placed_facilities {
+            let geom = geom_array.iter().find(|g| g["id"].as_str() == Some(facility.facility_id.as_str()));
+            let slots = |key: &str| geom.and_then(|g| g[key].as_u64()).unwrap_or(1).max(1) as usize;
+
+            for (buffer, count) in [
+                (&mut facility.input_buffer, slots("input_slots")),
+                (&mut facility.output_buffer, slots("output_slots")),
+            ] {
+                if buffer.len() == count { continue; }
+                buffer.retain(|s| !s.is_empty());
+                buffer.truncate(count);
+                buffer.resize_with(count, BufferSlot::empty);
+            }
+        }
+    }
+
+    /// Checks a full layout against this plate without touching the grid
+    pub fn validate_layout(&self, facilities: &[PlacedFacility], edges: &[LogisticsEdge], geometry: &serde_json::Value) -> Vec<PlacementIssue> {
+        let mut issues = Vec::new();
+        // Index of the facility covering each cell
+        let mut owners: Vec<Option<usize>> = vec![None; (self.width * self.height) as usize];
+        let mut seen: HashSet<&str> = HashSet::new();
+
+        for (i, facility) in facilities.iter().enumerate() {
+            if !seen.insert(facility.instance_id.as_str()) {
+                issues.push(PlacementIssue::DuplicateInstance { instance_id: facility.instance_id.clone() });
+                continue;
+            }
+
+            let (w, h) = geometry::footprint(geometry, &facility.facility_id, facility.rotation);
+            if facility.x < 0 || facility.y < 0
+                || facility.x + w as i32 > self.width as i32 || facility.y + h as i32 > self.height as i32 {
+                issues.push(PlacementIssue::OutOfBounds {
+                    instance_id: facility.instance_id.clone(),
+                    x: facility.x,
+                    y: facility.y,
+                    width: w,
+                    height: h,
+                });
+                continue;
+            }
+            if let Some((x, y)) = self.terrain.blocked_in(facility.x, facility.y, w, h) {
+                issues.push(PlacementIssue::BlockedTerrain { instance_id: facility.instance_id.clone(), x, y });
+            }
+
+            let mut collision = None;
+            for dy in 0..h as i32 {
+                for dx in 0..w as i32 {
+                    let (x, y) = (facility.x + dx, facility.y + dy);
+                    let cell = &mut owners[(y as u32 * self.width + x as u32) as usize];
+                    match cell {
+                        Some(other) if collision.is_none() => collision = Some((*other, x, y)),
+                        Some(_) => {}
+                        None => *cell = Some(i),
+                    }
+                }
+            }
+            if let Some((other, x, y)) = collision {
+                issues.push(PlacementIssue::Collision {
+                    instance_id: facility.instance_id.clone(),
+                    other_instance_id: facilities[other].instance_id.clone(),
+                    x,
+                    y,
+                });
+            }
+        }
+
+        let find = |id: &str| facilities.iter().find(|f| f.instance_id == id);
+        for edge in edges {
+            for id in [&edge.from_instance_id, &edge.to_instance_id] {
+                if !seen.contains(id.as_str()) {
+                    issues.push(PlacementIssue::DanglingEdge { edge: edge.key(), missing_instance_id: id.clone() });
+                }
+            }
+            if let (Some(from), Some(to)) = (find(&edge.from_instance_id), find(&edge.to_instance_id)) {
+                for issue in edge_validation::port_issues(from, to, edge, geometry) {
+                    issues.push(PlacementIssue::InvalidEdge { edge: edge.key(), issue });
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Replaces the layout if `validate_layout` finds nothing wrong, then rebuilds
+    /// occupancy, buffers and the power grid. On failure the grid is left as it was.
+    pub fn apply_layout(
+        &mut self,
+        facilities: Vec<PlacedFacility>,
+        edges: Vec<LogisticsEdge>,
+        geometry: &serde_json::Value,
+    ) -> Result<(), Vec<PlacementIssue>> {
+        let issues = self.validate_layout(&facilities, &edges, geometry);
+        if !issues.is_empty() {
+            return Err(issues);
+        }
+
+        self.placed_facilities = facilities;
+        self.logistics_edges = edges;
+        self.resize_buffers(geometry);
+        self.rebuild_occupancy(geometry);
+        self.update_power_grid(geometry);
+        Ok(())
+    }
+
+    /// Recomputes `occupancy` and `mined_items` from the placed facilities' footprints.
+    /// Cells outside the plate are ignored.
+    pub fn rebuild_occupancy(&mut self, geometry: &serde_json::Value) {
+        self.occupancy = vec![false; (self.width * self.height) as usize];
+        self.mined_items.clear();
+        for facility in &self.placed_facilities {
+            let (w, h) = geometry::footprint(geometry, &facility.facility_id, facility.rotation);
+            if let Some(item_id) = self.mined_item(facility, w, h) {
+                self.mined_items.insert(facility.instance_id.clone(), item_id);
+            }
+            for dy in 0..h as i32 {
+                for dx in 0..w as i32 {
+                    let (x, y) = (facility.x + dx, facility.y + dy);
+                    if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 { continue; }
+                    self.occupancy[(y as u32 * self.width + x as u32) as usize] = true;
+                }
+            }
+        }
+    }
+
+    pub fn is_area_clear(&self, x: i32, y: i32, w: u32, h: u32) -> bool {
+        for dy in 0..h {
+            for dx in 0..w {
+                let curr_x = x + dx as i32;
+                let curr_y = y + dy as i32;
+                
+                if curr_x < 0 || curr_x >= self.width as i32 || curr_y < 0 || curr_y >= self.height as i32 {
+                    return false;
+                }
+                
+                if self.occupancy[(curr_y as u32 * self.width + curr_x as u32) as usize]
+                    || self.terrain.is_blocked(curr_x, curr_y) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    pub fn place_facility(&mut self, facility: PlacedFacility, meta: &Facility) -> bool {
+        let (w, h) = geometry::rotated_size(meta.width, meta.height, facility.rotation);
+        if self.is_area_clear(facility.x, facility.y, w, h) {
+            // Mark occupancy
+            self.mark_area(facility.x, facility.y, w, h);
+            if let Some(item_id) = self.mined_item(&facility, w, h) {
+                self.mined_items.insert(facility.instance_id.clone(), item_id);
+            }
+            self.placed_facilities.push(facility);
+            return true;
+        }
+        false
+    }
+
+    /// Resource a miner with a `w` x `h` footprint extracts where it stands
+    fn mined_item(&self, facility: &PlacedFacility, w: u32, h: u32) -> Option<String> {
+        if !self.miner_ids.contains(&facility.facility_id) {
+            return None;
+        }
+        self.terrain.resource_in(facility.x, facility.y, w, h).map(|item| item.to_string())
+    }
+
+    /// Flags the area as occupied; the caller checks `is_area_clear` first
+    pub fn mark_area(&mut self, x: i32, y: i32, w: u32, h: u32) {
+        for dy in 0..h {
+            for dx in 0..w {
+                let curr_x = (x + dx as i32) as u32;
+                let curr_y = (y + dy as i32) as u32;
+                self.occupancy[(curr_y * self.width + curr_x) as usize] = true;
+            }
+        }
+    }
+
+    /// First free `"{facility_id}_{n}"` id on this grid
+    pub fn next_instance_id(&self, facility_id: &str) -> String {
+        self.next_instance_id_excluding(facility_id, &HashSet::new())
+    }
+
+    /// Like `next_instance_id`, also skipping ids handed out for facilities not placed yet
+    pub fn next_instance_id_excluding(&self, facility_id: &str, reserved: &HashSet<String>) -> String {
+        let taken: HashSet<&str> = self.placed_facilities.iter().map(|f| f.instance_id.as_str()).collect();
+        (0..)
+            .map(|n| format!("{}_{}", facility_id, n))
+            .find(|id| !taken.contains(id.as_str()) && !reserved.contains(id))
+            .unwrap()
+    }
+
+    pub fn get_distance(a: &PlacedFacility, b: &PlacedFacility) -> f32 {
+        let dx = (a.x - b.x) as f32;
+        let dy = (a.y - b.y) as f32;
+        (dx * dx + dy * dy).sqrt()
+    }
+}
@@ -0,0 +1,202 @@
+use crate::edge_validation;
+use crate::error::EngineError;
+use crate::facility::{Facility, PlacedFacility};
+use crate::grid::GridState;
+use crate::logistics::LogisticsEdge;
+use serde::{Deserialize, Serialize};
+
+/// A single change to the grid, applied with `GridState::apply_edit`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum GridEdit {
+    /// An empty `instance_id` gets one from `GridState::next_instance_id`
+    PlaceFacility { facility: PlacedFacility },
+    MoveFacility { instance_id: String, x: i32, y: i32 },
+    RotateFacility { instance_id: String, rotation: u32 },
+    /// Also drops every edge touching the facility
+    RemoveFacility { instance_id: String },
+    AddEdge { edge: LogisticsEdge },
+    /// Matched by `LogisticsEdge::key`
+    RemoveEdge { key: String },
+}
+
+/// What an edit did, so the frontend can patch its state instead of resyncing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GridChange {
+    pub placed: Vec<PlacedFacility>,
+    pub updated: Vec<PlacedFacility>,
+    /// `updated` facilities as they were before the edit, same order
+    pub previous: Vec<PlacedFacility>,
+    pub removed: Vec<PlacedFacility>,
+    pub edges_added: Vec<LogisticsEdge>,
+    pub edges_removed: Vec<LogisticsEdge>,
+}
+
+impl GridChange {
+    /// Edits that take the grid back to where it was before this change, in apply order
+    pub fn inverse(&self) -> Vec<GridEdit> {
+        let mut edits: Vec<GridEdit> = self.edges_added.iter()
+            .map(|e| GridEdit::RemoveEdge { key: e.key() })
+            .collect();
+        edits.extend(self.placed.iter().map(|f| GridEdit::RemoveFacility { instance_id: f.instance_id.clone() }));
+        edits.extend(self.removed.iter().map(|f| GridEdit::PlaceFacility { facility: f.clone() }));
+        for (now, before) in self.updated.iter().zip(&self.previous) {
+            if (now.x, now.y) != (before.x, before.y) {
+                edits.push(GridEdit::MoveFacility { instance_id: before.instance_id.clone(), x: before.x, y: before.y });
+            }
+            if now.rotation != before.rotation {
+                edits.push(GridEdit::RotateFacility { instance_id: before.instance_id.clone(), rotation: before.rotation });
+            }
+        }
+        edits.extend(self.edges_removed.iter().map(|e| GridEdit::AddEdge { edge: e.clone() }));
+        edits
+    }
+}
+
+impl GridEdit {
+    /// Short description for the history list
+    pub fn describe(&self) -> String {
+        match self {
+            GridEdit::PlaceFacility { facility } => format!("Place {}", facility.facility_id),
+            GridEdit::MoveFacility { instance_id, .. } => format!("Move {}", instance_id),
+            GridEdit::RotateFacility { instance_id, .. } => format!("Rotate {}", instance_id),
+            GridEdit::RemoveFacility { instance_id } => format!("Remove {}", instance_id),
+            GridEdit::AddEdge { edge } => format!("Connect {}", edge.key()),
+            GridEdit::RemoveEdge { key } => format!("Disconnect {}", key),
+        }
+    }
+}
+
+impl GridState {
+    /// Validates and applies one edit. Facilities the edit doesn't touch keep their
+    /// buffers and crafting state; on error the grid is unchanged.
+    pub fn apply_edit(&mut self, edit: GridEdit, geometry: &serde_json::Value) -> Result<GridChange, EngineError> {
+        let mut change = GridChange::default();
+
+        match edit {
+            GridEdit::PlaceFacility { mut facility } => {
+                let meta = Self::facility_meta(geometry, &facility.facility_id)?;
+                if facility.instance_id.is_empty() {
+                    facility.instance_id = self.next_instance_id(&facility.facility_id);
+                } else if self.index_of(&facility.instance_id).is_ok() {
+                    return Err(EngineError::Duplicate { kind: "facility", id: facility.instance_id });
+                }
+                facility.rotation = Self::normalize_rotation(facility.rotation)?;
+
+                let (x, y) = (facility.x, facility.y);
+                if !self.place_facility(facility, &meta) {
+                    return Err(EngineError::Collision { facility_id: meta.id, x, y });
+                }
+                self.resize_buffers(geometry);
+                change.placed.push(self.placed_facilities.last().unwrap().clone());
+            }
+            GridEdit::MoveFacility { instance_id, x, y } => {
+                let index = self.index_of(&instance_id)?;
+                let previous = self.placed_facilities[index].clone();
+                change.updated.push(self.relocate(index, x, y, previous.rotation, geometry)?);
+                change.previous.push(previous);
+            }
+            GridEdit::RotateFacility { instance_id, rotation } => {
+                let index = self.index_of(&instance_id)?;
+                let rotation = Self::normalize_rotation(rotation)?;
+                let previous = self.placed_facilities[index].clone();
+                change.updated.push(self.relocate(index, previous.x, previous.y, rotation, geometry)?);
+                change.previous.push(previous);
+            }
+            GridEdit::RemoveFacility { instance_id } => {
+                let index = self.index_of(&instance_id)?;
+                change.removed.push(self.placed_facilities.remove(index));
+
+                let (removed, kept) = std::mem::take(&mut self.logistics_edges).into_iter()
+                    .partition(|e| e.from_instance_id == instance_id || e.to_instance_id == instance_id);
+                self.logistics_edges = kept;
+                for edge in &removed {
+                    self.edge_progress.remove(&edge.key());
+                }
+                change.edges_removed = removed;
+                self.rebuild_occupancy(geometry);
+            }
+            GridEdit::AddEdge { edge } => {
+                if edge.from_instance_id == edge.to_instance_id {
+                    return Err(EngineError::RejectedEdge { key: edge.key(), reason: "connects a facility to itself".to_string() });
+                }
+                if self.logistics_edges.iter().any(|e| e.key() == edge.key()) {
+                    return Err(EngineError::Duplicate { kind: "edge", id: edge.key() });
+                }
+                let from = &self.placed_facilities[self.index_of(&edge.from_instance_id)?];
+                let to = &self.placed_facilities[self.index_of(&edge.to_instance_id)?];
+                if let Some(issue) = edge_validation::port_issues(from, to, &edge, geometry).first() {
+                    return Err(EngineError::RejectedEdge { key: edge.key(), reason: issue.describe() });
+                }
+                self.logistics_edges.push(edge.clone());
+                change.edges_added.push(edge);
+            }
+            GridEdit::RemoveEdge { key } => {
+                let index = self.logistics_edges.iter().position(|e| e.key() == key)
+                    .ok_or_else(|| EngineError::MissingEdge { key: key.clone() })?;
+                self.edge_progress.remove(&key);
+                change.edges_removed.push(self.logistics_edges.remove(index));
+            }
+        }
+
+        self.update_power_grid(geometry);
+        Ok(change)
+    }
+
+    /// Applies the edits in order, all or nothing: if one fails, the ones before it are reverted
+    pub fn apply_edits(&mut self, edits: Vec<GridEdit>, geometry: &serde_json::Value) -> Result<Vec<GridChange>, EngineError> {
+        let mut changes = Vec::new();
+        for edit in edits {
+            match self.apply_edit(edit, geometry) {
+                Ok(change) => changes.push(change),
+                Err(e) => {
+                    for change in changes.iter().rev() {
+                        for undo in change.inverse() {
+                            // Reverting an edit that just succeeded can't collide
+                            let _ = self.apply_edit(undo, geometry);
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    fn index_of(&self, instance_id: &str) -> Result<usize, EngineError> {
+        self.placed_facilities.iter().position(|f| f.instance_id == instance_id)
+            .ok_or_else(|| EngineError::missing_facility(instance_id))
+    }
+
+    fn facility_meta(geometry: &serde_json::Value, facility_id: &str) -> Result<Facility, EngineError> {
+        geometry.as_array()
+            .and_then(|a| a.iter().find(|f| f["id"].as_str() == Some(facility_id)))
+            .and_then(|f| serde_json::from_value(f.clone()).ok())
+            .ok_or_else(|| EngineError::missing_facility(facility_id))
+    }
+
+    fn normalize_rotation(rotation: u32) -> Result<u32, EngineError> {
+        if !rotation.is_multiple_of(90) {
+            return Err(EngineError::invalid(format!("Rotation must be a multiple of 90, got {}", rotation)));
+        }
+        Ok(rotation % 360)
+    }
+
+    /// Re-places the facility at `index` through `place_facility`, keeping its slot in
+    /// `placed_facilities` and all of its state. Puts it back where it was on collision.
+    fn relocate(&mut self, index: usize, x: i32, y: i32, rotation: u32, geometry: &serde_json::Value) -> Result<PlacedFacility, EngineError> {
+        let meta = Self::facility_meta(geometry, &self.placed_facilities[index].facility_id)?;
+        let original = self.placed_facilities.remove(index);
+        self.rebuild_occupancy(geometry);
+
+        if self.place_facility(PlacedFacility { x, y, rotation, ..original.clone() }, &meta) {
+            let moved = self.placed_facilities.pop().unwrap();
+            self.placed_facilities.insert(index, moved.clone());
+            Ok(moved)
+        } else {
+            self.placed_facilities.insert(index, original);
+            self.rebuild_occupancy(geometry);
+            Err(EngineError::Collision { facility_id: meta.id, x, y })
+        }
+    }
+}
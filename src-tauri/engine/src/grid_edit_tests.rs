@@ -0,0 +1,70 @@
+use crate::data_loader::DataLoader;
+use crate::facility::PlacedFacility;
+use crate::grid::GridState;
+use crate::grid_edit::GridEdit;
+use crate::logistics::LogisticsEdge;
+
+/// PAC at (0, 0) belted into a furnace at (10, 0)
+fn grid_with_chain(geometry: &serde_json::Value) -> GridState {
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("", "hub_pac_main", 0, 0) }, geometry).unwrap();
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("", "item_port_furnance_1", 10, 0) }, geometry).unwrap();
+    grid.apply_edit(GridEdit::AddEdge { edge: LogisticsEdge::new("hub_pac_main_0", "out_r1", "item_port_furnance_1_0", "in_1", "item_iron_ore") }, geometry).unwrap();
+    grid
+}
+
+#[test]
+fn test_place_assigns_ids_and_rejects_overlap() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = grid_with_chain(&geometry);
+
+    assert_eq!(grid.placed_facilities[1].instance_id, "item_port_furnance_1_0");
+    assert_eq!(grid.placed_facilities[1].input_buffer.len(), 2);
+    assert!(!grid.power_grid.is_unpowered("item_port_furnance_1_0"));
+
+    let overlap = GridEdit::PlaceFacility { facility: PlacedFacility::new("", "item_port_furnance_1", 11, 1) };
+    assert_eq!(grid.apply_edit(overlap, &geometry).unwrap_err().code(), "collision");
+    assert_eq!(grid.placed_facilities.len(), 2);
+}
+
+#[test]
+fn test_move_and_rotate_keep_state() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = grid_with_chain(&geometry);
+    grid.placed_facilities[0].output_buffer[0].quantity = 7;
+    grid.placed_facilities[1].active_recipe_id = Some("furnance_iron_nugget_1".to_string());
+
+    let change = grid.apply_edit(GridEdit::MoveFacility { instance_id: "item_port_furnance_1_0".to_string(), x: 20, y: 20 }, &geometry).unwrap();
+    assert_eq!((change.updated[0].x, change.updated[0].y), (20, 20));
+    assert_eq!(change.updated[0].active_recipe_id.as_deref(), Some("furnance_iron_nugget_1"));
+    assert_eq!(grid.placed_facilities[0].output_buffer[0].quantity, 7);
+    assert!(grid.is_area_clear(10, 0, 3, 3));
+    assert!(!grid.is_area_clear(20, 20, 3, 3));
+
+    // Winder is 6x4: rotating it at the plate edge would push it off
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("", "item_port_winder_1", 0, 28) }, &geometry).unwrap();
+    let rotate = GridEdit::RotateFacility { instance_id: "item_port_winder_1_0".to_string(), rotation: 90 };
+    assert!(grid.apply_edit(rotate, &geometry).is_err());
+    assert_eq!(grid.placed_facilities[2].rotation, 0);
+    assert!(!grid.is_area_clear(5, 31, 1, 1));
+}
+
+#[test]
+fn test_remove_facility_drops_its_edges() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = grid_with_chain(&geometry);
+
+    let duplicate = GridEdit::AddEdge { edge: LogisticsEdge::new("hub_pac_main_0", "out_r1", "item_port_furnance_1_0", "in_1", "item_iron_ore") };
+    assert_eq!(grid.apply_edit(duplicate, &geometry).unwrap_err().code(), "duplicate");
+    let dangling = GridEdit::AddEdge { edge: LogisticsEdge::new("hub_pac_main_0", "out_r1", "nowhere", "in_1", "item_iron_ore") };
+    assert!(grid.apply_edit(dangling, &geometry).is_err());
+
+    let change = grid.apply_edit(GridEdit::RemoveFacility { instance_id: "item_port_furnance_1_0".to_string() }, &geometry).unwrap();
+    assert_eq!(change.removed.len(), 1);
+    assert_eq!(change.edges_removed.len(), 1);
+    assert!(grid.logistics_edges.is_empty());
+    assert!(grid.is_area_clear(10, 0, 3, 3));
+
+    let missing = GridEdit::RemoveEdge { key: "hub_pac_main_0:out_r1->item_port_furnance_1_0:in_1".to_string() };
+    assert_eq!(grid.apply_edit(missing, &geometry).unwrap_err().code(), "missing_edge");
+}
@@ -0,0 +1,70 @@
+use crate::data_loader::DataLoader;
+use crate::facility::PlacedFacility;
+use crate::grid::{GridState, PlacementIssue};
+use crate::logistics::LogisticsEdge;
+
+#[test]
+fn test_apply_layout_rebuilds_occupancy() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+
+    // Winder is 6x4; rotated it covers 4x6
+    let layout = vec![
+        PlacedFacility::new("pac", "hub_pac_main", 0, 0),
+        PlacedFacility { rotation: 90, ..PlacedFacility::new("winder", "item_port_winder_1", 10, 0) },
+    ];
+    grid.apply_layout(layout, vec![LogisticsEdge::new("pac", "out_r1", "winder", "in_1", "placeholder")], &geometry).unwrap();
+
+    assert!(!grid.is_area_clear(8, 8, 1, 1));
+    assert!(!grid.is_area_clear(13, 5, 1, 1));
+    assert!(grid.is_area_clear(14, 0, 2, 4));
+    assert_eq!(grid.placed_facilities[1].output_buffer.len(), 1);
+}
+
+#[test]
+fn test_apply_layout_rejects_bad_state() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    grid.apply_layout(vec![PlacedFacility::new("pac", "hub_pac_main", 0, 0)], Vec::new(), &geometry).unwrap();
+
+    let layout = vec![
+        PlacedFacility::new("pac", "hub_pac_main", 0, 0),
+        PlacedFacility::new("a", "item_port_furnance_1", 8, 8),
+        PlacedFacility::new("b", "item_port_furnance_1", 30, 0),
+        PlacedFacility::new("a", "item_port_furnance_1", 20, 20),
+    ];
+    let issues = grid.apply_layout(layout, vec![LogisticsEdge::new("pac", "out_r1", "gone", "in_1", "placeholder")], &geometry).unwrap_err();
+
+    assert_eq!(issues, vec![
+        PlacementIssue::Collision {
+            instance_id: "a".to_string(),
+            other_instance_id: "pac".to_string(),
+            x: 8,
+            y: 8,
+        },
+        PlacementIssue::OutOfBounds { instance_id: "b".to_string(), x: 30, y: 0, width: 3, height: 3 },
+        PlacementIssue::DuplicateInstance { instance_id: "a".to_string() },
+        PlacementIssue::DanglingEdge {
+            edge: "pac:out_r1->gone:in_1".to_string(),
+            missing_instance_id: "gone".to_string(),
+        },
+    ]);
+    // Previous layout kept
+    assert_eq!(grid.placed_facilities.len(), 1);
+    assert!(grid.is_area_clear(9, 0, 3, 3));
+}
+
+#[test]
+fn test_clone_keeps_runtime_state() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    grid.apply_layout(vec![PlacedFacility::new("pac", "hub_pac_main", 0, 0)], Vec::new(), &geometry).unwrap();
+    grid.sim_time = 12.5;
+
+    // Unlike a serde round-trip, occupancy and the clock survive
+    let copy = grid.clone();
+    assert_eq!(copy.occupancy, grid.occupancy);
+    assert!(!copy.is_area_clear(0, 0, 1, 1));
+    assert_eq!(copy.sim_time, 12.5);
+    assert_eq!(copy.power_grid.powered_facilities, grid.power_grid.powered_facilities);
+}
@@ -0,0 +1,114 @@
+use crate::error::EngineError;
+use crate::grid::GridState;
+use crate::grid_edit::{GridChange, GridEdit};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Undo steps kept when no limit is given
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// One undoable step: a single edit or a group of them (e.g. a blueprint paste)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub label: String,
+    /// Replayed by redo. Placements carry the instance id they were given.
+    edits: Vec<GridEdit>,
+    /// Replayed by undo
+    inverse: Vec<GridEdit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistorySummary {
+    /// Oldest first; the last entry is what `undo` reverts
+    pub undo: Vec<String>,
+    /// The last entry is what `redo` reapplies
+    pub redo: Vec<String>,
+}
+
+/// Command-pattern history of grid edits. Only edits that went through `apply`
+/// can be undone, so anything that replaces the grid wholesale should `clear` it.
+#[derive(Debug)]
+pub struct EditHistory {
+    undo_stack: VecDeque<Transaction>,
+    redo_stack: Vec<Transaction>,
+    limit: usize,
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl EditHistory {
+    pub fn new(limit: usize) -> Self {
+        Self { undo_stack: VecDeque::new(), redo_stack: Vec::new(), limit: limit.max(1) }
+    }
+
+    /// Applies the edits as one transaction (all or nothing) and records it
+    pub fn apply(
+        &mut self,
+        grid: &mut GridState,
+        label: &str,
+        edits: Vec<GridEdit>,
+        geometry: &serde_json::Value,
+    ) -> Result<Vec<GridChange>, EngineError> {
+        let changes = grid.apply_edits(edits.clone(), geometry)?;
+
+        // Replay placements with the ids they actually got
+        let edits = edits.into_iter().zip(&changes).map(|(edit, change)| match (edit, change.placed.first()) {
+            (GridEdit::PlaceFacility { .. }, Some(placed)) => GridEdit::PlaceFacility { facility: placed.clone() },
+            (edit, _) => edit,
+        }).collect();
+        let inverse = changes.iter().rev().flat_map(|c| c.inverse()).collect();
+
+        self.undo_stack.push_back(Transaction { label: label.to_string(), edits, inverse });
+        if self.undo_stack.len() > self.limit {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+        Ok(changes)
+    }
+
+    /// Reverts the latest transaction. `Ok(None)` when there is nothing to undo.
+    pub fn undo(&mut self, grid: &mut GridState, geometry: &serde_json::Value) -> Result<Option<Vec<GridChange>>, EngineError> {
+        let Some(transaction) = self.undo_stack.pop_back() else { return Ok(None) };
+        match grid.apply_edits(transaction.inverse.clone(), geometry) {
+            Ok(changes) => {
+                self.redo_stack.push(transaction);
+                Ok(Some(changes))
+            }
+            Err(e) => {
+                self.undo_stack.push_back(transaction);
+                Err(e)
+            }
+        }
+    }
+
+    /// Reapplies the latest undone transaction. `Ok(None)` when there is nothing to redo.
+    pub fn redo(&mut self, grid: &mut GridState, geometry: &serde_json::Value) -> Result<Option<Vec<GridChange>>, EngineError> {
+        let Some(transaction) = self.redo_stack.pop() else { return Ok(None) };
+        match grid.apply_edits(transaction.edits.clone(), geometry) {
+            Ok(changes) => {
+                self.undo_stack.push_back(transaction);
+                Ok(Some(changes))
+            }
+            Err(e) => {
+                self.redo_stack.push(transaction);
+                Err(e)
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    pub fn summary(&self) -> HistorySummary {
+        HistorySummary {
+            undo: self.undo_stack.iter().map(|t| t.label.clone()).collect(),
+            redo: self.redo_stack.iter().map(|t| t.label.clone()).collect(),
+        }
+    }
+}
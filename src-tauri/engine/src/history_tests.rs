@@ -0,0 +1,106 @@
+use crate::blueprint::Blueprint;
+use crate::data_loader::DataLoader;
+use crate::facility::PlacedFacility;
+use crate::grid::GridState;
+use crate::grid_edit::GridEdit;
+use crate::history::EditHistory;
+use crate::logistics::LogisticsEdge;
+
+fn place(facility_id: &str, x: i32, y: i32) -> GridEdit {
+    GridEdit::PlaceFacility {
+        facility: PlacedFacility::new("", facility_id, x, y),
+    }
+}
+
+fn connect(from: &str, to: &str) -> GridEdit {
+    GridEdit::AddEdge {
+        edge: LogisticsEdge::new(from, "out_r1", to, "in_1", "item_iron_ore"),
+    }
+}
+
+fn positions(grid: &GridState) -> Vec<(String, i32, i32, u32)> {
+    grid.placed_facilities.iter().map(|f| (f.instance_id.clone(), f.x, f.y, f.rotation)).collect()
+}
+
+#[test]
+fn test_undo_redo_single_edits() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    let mut history = EditHistory::default();
+
+    let furnace = "item_port_furnance_1_0".to_string();
+    history.apply(&mut grid, "place pac", vec![place("hub_pac_main", 0, 0)], &geometry).unwrap();
+    history.apply(&mut grid, "place furnace", vec![place("item_port_furnance_1", 10, 0)], &geometry).unwrap();
+    history.apply(&mut grid, "connect", vec![connect("hub_pac_main_0", &furnace)], &geometry).unwrap();
+    let connected = positions(&grid);
+    grid.placed_facilities[1].input_buffer[0].item_id = "item_iron_ore".to_string();
+    grid.placed_facilities[1].input_buffer[0].quantity = 4;
+
+    history.apply(&mut grid, "move", vec![GridEdit::MoveFacility { instance_id: furnace.clone(), x: 20, y: 20 }], &geometry).unwrap();
+    history.apply(&mut grid, "rotate", vec![GridEdit::RotateFacility { instance_id: furnace.clone(), rotation: 90 }], &geometry).unwrap();
+    history.apply(&mut grid, "remove", vec![GridEdit::RemoveFacility { instance_id: furnace.clone() }], &geometry).unwrap();
+    assert!(grid.logistics_edges.is_empty());
+
+    for _ in 0..3 {
+        history.undo(&mut grid, &geometry).unwrap().unwrap();
+    }
+    assert_eq!(positions(&grid), connected);
+    assert_eq!(grid.logistics_edges.len(), 1);
+    // Restored with its state, not as a fresh placement
+    assert_eq!(grid.placed_facilities[1].input_buffer[0].quantity, 4);
+    assert!(grid.is_area_clear(20, 20, 3, 3));
+
+    history.redo(&mut grid, &geometry).unwrap().unwrap();
+    assert_eq!((grid.placed_facilities[1].x, grid.placed_facilities[1].y), (20, 20));
+    assert_eq!(history.summary().redo, vec!["remove", "rotate"]);
+
+    // A new edit drops the redo branch
+    history.apply(&mut grid, "place other", vec![place("item_port_furnance_1", 0, 20)], &geometry).unwrap();
+    assert!(history.summary().redo.is_empty());
+    assert!(history.redo(&mut grid, &geometry).unwrap().is_none());
+}
+
+#[test]
+fn test_blueprint_paste_undoes_as_one_step() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    let mut history = EditHistory::default();
+    history.apply(&mut grid, "place pac", vec![place("hub_pac_main", 0, 0)], &geometry).unwrap();
+    history.apply(&mut grid, "place furnace", vec![place("item_port_furnance_1", 10, 0)], &geometry).unwrap();
+    history.apply(&mut grid, "connect", vec![connect("hub_pac_main_0", "item_port_furnance_1_0")], &geometry).unwrap();
+
+    let blueprint = Blueprint::capture(&grid, &geometry, 0, 0, 13, 9);
+    let edits = blueprint.paste_edits(&grid, &geometry, 0, 12, 0).unwrap();
+    let changes = history.apply(&mut grid, "paste", edits, &geometry).unwrap();
+    assert_eq!(changes.len(), 3);
+    assert_eq!(grid.placed_facilities.len(), 4);
+    assert_eq!(grid.logistics_edges.len(), 2);
+
+    history.undo(&mut grid, &geometry).unwrap().unwrap();
+    assert_eq!(grid.placed_facilities.len(), 2);
+    assert_eq!(grid.logistics_edges.len(), 1);
+    assert_eq!(history.summary().undo.last().map(String::as_str), Some("connect"));
+
+    // Failing transactions are rolled back and not recorded
+    let edits = blueprint.paste_edits(&grid, &geometry, 0, 26, 0).unwrap();
+    assert!(history.apply(&mut grid, "paste off plate", edits, &geometry).is_err());
+    assert_eq!(grid.placed_facilities.len(), 2);
+    assert_eq!(history.summary().undo.len(), 3);
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    let mut history = EditHistory::new(2);
+
+    for x in [0, 4, 8, 12] {
+        history.apply(&mut grid, &format!("place {}", x), vec![place("item_port_furnance_1", x, 0)], &geometry).unwrap();
+    }
+
+    assert_eq!(history.summary().undo, vec!["place 8", "place 12"]);
+    history.undo(&mut grid, &geometry).unwrap();
+    history.undo(&mut grid, &geometry).unwrap();
+    assert!(history.undo(&mut grid, &geometry).unwrap().is_none());
+    assert_eq!(grid.placed_facilities.len(), 2);
+}
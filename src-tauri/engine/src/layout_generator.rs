@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::engine::data_loader::DataLoader;
+use crate::geometry::Shape;
+use crate::logistics::LogisticsEdge;
+use crate::rate::Rate;
+use crate::terrain::Terrain;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlacedFacilityLayout {
+    /// Referenced by `LayoutCandidate::edges`; filled by the generator
+    #[serde(default)]
+    pub instance_id: String,
     pub facility_id: String,
     pub x: i32,
     pub y: i32,
@@ -16,9 +22,24 @@ pub struct LayoutCandidate {
     pub facilities: Vec<PlacedFacilityLayout>,
     pub score: f64,
     pub power_consumption: f64,
-    pub items_per_hour: HashMap<String, f64>,
+    /// Output per target item, in the units the plan carries
+    pub rates: HashMap<String, Rate>,
     pub efficiency: f64,
     pub limiting_factor: Option<String>,
+    /// Routed belts between facilities (empty until routing has run)
+    #[serde(default)]
+    pub edges: Vec<LogisticsEdge>,
+}
+
+impl PlacedFacilityLayout {
+    /// Instance id used when loading the layout into a grid
+    pub fn resolved_instance_id(&self, index: usize) -> String {
+        if self.instance_id.is_empty() {
+            format!("{}_{}", self.facility_id, index)
+        } else {
+            self.instance_id.clone()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,15 +50,31 @@ pub struct LayoutConstraints {
     pub power_source_x: i32, // User preferred or center (-1 for center)
     pub power_source_y: i32, // User preferred or center (-1 for center)
     pub max_power_budget: Option<f64>,
+    /// Blocked cells are never built on or used for port access
+    #[serde(default)]
+    pub terrain: Terrain,
+}
+
+impl LayoutConstraints {
+    /// Empty plate with the power source at the origin and no power cap
+    pub fn for_plate(plate_width: i32, plate_height: i32) -> Self {
+        Self {
+            plate_width,
+            plate_height,
+            power_source_type: "pac".to_string(),
+            power_source_x: 0,
+            power_source_y: 0,
+            max_power_budget: None,
+            terrain: Terrain::default(),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-struct Port {
-    id: String,
-    x: i32, // Local relative to facility origin
-    y: i32,
-    r#type: String, // "input" or "output"
+/// What one placement pass managed to fit
+struct Packing {
+    facilities: Vec<PlacedFacilityLayout>,
+    /// Instances that found no spot
+    unplaced: usize,
 }
 
 struct LayoutEngine {
@@ -48,12 +85,20 @@ struct LayoutEngine {
 }
 
 impl LayoutEngine {
-    fn new(width: i32, height: i32) -> Self {
-        Self {
+    fn new(width: i32, height: i32, terrain: &Terrain) -> Self {
+        let mut engine = Self {
             width,
             height,
             grid: vec![false; (width * height) as usize],
+        };
+        // Blocked terrain counts as occupied from the start
+        for &(x, y) in &terrain.blocked {
+            if x >= 0 && y >= 0 && x < width && y < height {
+                let idx = engine.index(x, y);
+                engine.grid[idx] = true;
+            }
         }
+        engine
     }
 
     fn index(&self, x: i32, y: i32) -> usize {
@@ -83,51 +128,23 @@ impl LayoutEngine {
         }
     }
 
-    /// Checks if ports have at least one free adjacent cell for belt connection
-    fn check_port_access(&self, x: i32, y: i32, w: i32, h: i32, ports: &[Port], rotation: i32) -> bool {
-        // Transform ports based on rotation
-        for port in ports {
-            let (px, py) = match rotation {
-                0 => (port.x, port.y),
-                90 => (h - 1 - port.y, port.x), // Rotate 90 deg clockwise
-                180 => (w - 1 - port.x, h - 1 - port.y),
-                270 => (port.y, w - 1 - port.x),
-                _ => (port.x, port.y), // Should not happen
-            };
-
-            let global_port_x = x + px;
-            let global_port_y = y + py;
-
-            // Heuristic: Check all 4 neighbors. If ALL are occupied (by other buildings), then port is blocked.
-            // Note: self.grid includes the current building we are checking? No, we haven't placed it yet.
-            // But we pass w, h which implies layout.
-            let neighbors = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-            let mut access_found = false;
-            for (nx, ny) in neighbors {
-                let tx = global_port_x + nx;
-                let ty = global_port_y + ny;
-                
-                // If out of bounds, it's blocked (walls)
-                if tx < 0 || ty < 0 || tx >= self.width || ty >= self.height {
-                    continue;
-                }
-                
-                // Check if occupied by OTHER existing buildings
-                // Also, ensure the neighbor is not part of the facility being placed itself.
-                let is_part_of_current_facility = (tx >= x && tx < x + w && ty >= y && ty < y + h) ||
-                                                   (tx >= x && tx < x + h && ty >= y && ty < y + w && (rotation == 90 || rotation == 270)); // For rotated
-                
-                if !is_part_of_current_facility && !self.grid[self.index(tx, ty)] {
-                    access_found = true;
-                    break;
-                }
-            }
-            
-            if !access_found {
-                return false;
+    /// Checks that every port's outside cell is on the plate and free for a belt
+    fn check_port_access(&self, x: i32, y: i32, shape: &Shape, rotation: u32) -> bool {
+        shape.world_ports(x, y, rotation).iter().all(|port| {
+            let (tx, ty) = port.outside_cell();
+            tx >= 0 && ty >= 0 && tx < self.width && ty < self.height && !self.grid[self.index(tx, ty)]
+        })
+    }
+
+    /// Keeps the cell outside each port free for its belt, so later machines can't wall it in
+    fn reserve_ports(&mut self, x: i32, y: i32, shape: &Shape, rotation: u32) {
+        for port in shape.world_ports(x, y, rotation) {
+            let (tx, ty) = port.outside_cell();
+            if tx >= 0 && ty >= 0 && tx < self.width && ty < self.height {
+                let idx = self.index(tx, ty);
+                self.grid[idx] = true;
             }
         }
-        true
     }
 
     /// Finds valid spot including port checks
@@ -135,9 +152,7 @@ impl LayoutEngine {
         &self,
         center_x: i32,
         center_y: i32,
-        w: i32,
-        h: i32,
-        ports: &[Port],
+        shape: &Shape,
     ) -> Option<(i32, i32, i32)> { // x, y, rotation
         let max_dim = self.width.max(self.height);
         let max_steps = (max_dim * max_dim) * 2;
@@ -152,21 +167,11 @@ impl LayoutEngine {
 
         for _ in 0..max_steps {
             // Try all 4 rotations at this coordinate
-            // 0: w x h
-            if !self.is_occupied(x, y, w, h) && self.check_port_access(x, y, w, h, ports, 0) {
-                return Some((x, y, 0));
-            }
-            // 90: h x w
-            if !self.is_occupied(x, y, h, w) && self.check_port_access(x, y, h, w, ports, 90) {
-                return Some((x, y, 90));
-            }
-             // 180: w x h
-            if !self.is_occupied(x, y, w, h) && self.check_port_access(x, y, w, h, ports, 180) {
-                return Some((x, y, 180));
-            }
-             // 270: h x w
-            if !self.is_occupied(x, y, h, w) && self.check_port_access(x, y, h, w, ports, 270) {
-                return Some((x, y, 270));
+            for rotation in [0, 90, 180, 270] {
+                let (w, h) = shape.footprint(rotation);
+                if !self.is_occupied(x, y, w as i32, h as i32) && self.check_port_access(x, y, shape, rotation) {
+                    return Some((x, y, rotation as i32));
+                }
             }
 
             // Spiral Move
@@ -198,7 +203,7 @@ impl LayoutGenerator {
         Self { constraints, geometry }
     }
 
-    fn get_facility_meta(&self, facility_type: &str) -> Option<(i32, i32, Vec<Port>)> {
+    fn get_facility_meta(&self, facility_type: &str) -> Option<Shape> {
         // In database.json, facilities is a List of objects.
         // We usually lookup by ID or Name. The `facility_type` passed here usually comes from the recipe's producer ID.
         // OR it's the "Name" (e.g. "PAC_MAIN").
@@ -208,42 +213,24 @@ impl LayoutGenerator {
         // Try ID match first, then Name match
         let facility = facilities.iter().find(|f| {
              f["id"].as_str() == Some(facility_type) || f["name"].as_str() == Some(facility_type)
-        });
-
-        if let Some(f) = facility {
-            let width = f["width"].as_i64()? as i32;
-            let height = f["height"].as_i64()? as i32;
-            
-            let mut ports = Vec::new();
-            if let Some(p_arr) = f["ports"].as_array() {
-                for p in p_arr {
-                    ports.push(Port {
-                        id: p["id"].as_str().unwrap_or("?").to_string(),
-                        x: p["x"].as_i64().unwrap_or(0) as i32,
-                        y: p["y"].as_i64().unwrap_or(0) as i32,
-                        r#type: p["type"].as_str().unwrap_or("input").to_string(),
-                    });
-                }
-            }
-            return Some((width, height, ports));
-        }
+        })?;
 
-        None
+        Shape::from_geometry(&self.geometry, facility["id"].as_str()?)
     }
 
-    fn generate_deterministic_layout(
-         &self,
-         required_facilities: &[(String, String, f64)],
-    ) -> Option<Vec<PlacedFacilityLayout>> {
+    /// Places the power source, then every required instance (counts rounded up).
+    /// None when the power source itself doesn't fit.
+    fn pack(&self, required_facilities: &[(String, String, f64)]) -> Option<Packing> {
         let w = self.constraints.plate_width;
         let h = self.constraints.plate_height;
-        let mut engine = LayoutEngine::new(w, h);
+        let mut engine = LayoutEngine::new(w, h, &self.constraints.terrain);
         let mut placed_list = Vec::new();
 
         // 1. Place Power Source (PAC) - Always Center
         let pac_type = &self.constraints.power_source_type;
         // Default 8x9 if not found, empty ports
-        let (pac_w, pac_h, pac_ports) = self.get_facility_meta(pac_type).unwrap_or((8, 9, vec![]));
+        let pac = self.get_facility_meta(pac_type).unwrap_or(Shape { width: 8, height: 9, ports: vec![] });
+        let (pac_w, pac_h) = (pac.width as i32, pac.height as i32);
         
         // Configurable center vs absolute
         let center_x = if self.constraints.power_source_x >= 0 { self.constraints.power_source_x } else { (w - pac_w) / 2 };
@@ -253,7 +240,9 @@ impl LayoutGenerator {
         // For PAC, we assume it doesn't have ports that need external access, or it's handled differently.
         if !engine.is_occupied(center_x, center_y, pac_w, pac_h) {
             engine.mark_occupied(center_x, center_y, pac_w, pac_h);
+            engine.reserve_ports(center_x, center_y, &pac, 0);
             placed_list.push(PlacedFacilityLayout {
+                instance_id: format!("{}_0", pac_type),
                 facility_id: pac_type.clone(),
                 x: center_x,
                 y: center_y,
@@ -266,20 +255,23 @@ impl LayoutGenerator {
         // 2. Sort Facilities (Priority: Producers -> Consumers to keep belts short?)
         // Or Cluster by connectivity?
         // For "Constraint-Limited", we want to just pack them effectively first.
+        let mut unplaced = 0;
         let mut sorted_reqs: Vec<&(String, String, f64)> = required_facilities.iter().collect();
         // Sort by count descending (place big groups first)
         sorted_reqs.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap()); 
 
         for (facility_id, facility_type, count) in sorted_reqs {
-            let instances = count.ceil() as i32;
-            let (fw, fh, f_ports) = self.get_facility_meta(facility_type).unwrap_or((3, 3, vec![]));
+            let instances = (count - 1e-9).ceil() as i32; // 3.0000000001 machines is still 3
+            let shape = self.get_facility_meta(facility_type).unwrap_or(Shape { width: 3, height: 3, ports: vec![] });
             
             for _ in 0..instances {
                 // Find spot using Port-Aware Spiral Search
-                if let Some((x, y, rot)) = engine.find_valid_spot(center_x, center_y, fw, fh, &f_ports) {
-                    let (rw, rh) = if rot % 180 == 0 { (fw, fh) } else { (fh, fw) };
-                    engine.mark_occupied(x, y, rw, rh);
+                if let Some((x, y, rot)) = engine.find_valid_spot(center_x, center_y, &shape) {
+                    let (rw, rh) = shape.footprint(rot as u32);
+                    engine.mark_occupied(x, y, rw as i32, rh as i32);
+                    engine.reserve_ports(x, y, &shape, rot as u32);
                     placed_list.push(PlacedFacilityLayout {
+                        instance_id: format!("{}_{}", facility_id, placed_list.len()),
                         facility_id: facility_id.clone(),
                         x, 
                         y,
@@ -290,18 +282,46 @@ impl LayoutGenerator {
                     // In a "Constraint Limited" simulator, we should probably stop or warn.
                     // But to return a partial VALID layout is better than nothing.
                     // We continue to see if smaller things fit.
-                    continue; 
+                    unplaced += 1;
                 }
             }
         }
         
-        Some(placed_list)
+        Some(Packing { facilities: placed_list, unplaced })
     }
 
+    /// Largest share (0 to 1) of `required_facilities` this generator can place, with ports
+    /// reachable and their belt cells kept free. 0 when not even the power source and one
+    /// machine of each type fit.
+    pub fn packing_scale(&self, required_facilities: &[(String, String, f64)]) -> f64 {
+        let fits = |scale: f64| {
+            let scaled: Vec<(String, String, f64)> = required_facilities.iter()
+                .map(|(id, facility_type, count)| (id.clone(), facility_type.clone(), count * scale))
+                .collect();
+            self.pack(&scaled).is_some_and(|packing| packing.unplaced == 0)
+        };
+        if fits(1.0) {
+            return 1.0;
+        }
+
+        // Bisect down to a tenth of a percent
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..10 {
+            let mid = (low + high) / 2.0;
+            if fits(mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    #[tracing::instrument(level = "info", name = "layout", skip_all, fields(facility_types = required_facilities.len(), plate_width = self.constraints.plate_width, plate_height = self.constraints.plate_height))]
     pub fn generate_layouts(
         &self,
         required_facilities: Vec<(String, String, f64)>,
-        target_items: &[(String, f64)],
+        target_items: &[(String, Rate)],
         _num_candidates: usize,
     ) -> Vec<LayoutCandidate> {
         // Load config to check hard limits if needed (optional)
@@ -309,14 +329,15 @@ impl LayoutGenerator {
 
         let mut candidates = Vec::new();
 
-        if let Some(layout) = self.generate_deterministic_layout(&required_facilities) {
+        if let Some(Packing { facilities: layout, unplaced }) = self.pack(&required_facilities) {
+             if unplaced > 0 {
+                 tracing::debug!(unplaced, "some instances found no spot");
+             }
              let power_consumption: f64 = required_facilities.iter()
                     .map(|(_, _, count)| count * 10.0) // Placeholder, should come from facility data
                     .sum();
              
-             let items_per_hour: HashMap<String, f64> = target_items.iter()
-                    .map(|(id, rate)| (id.clone(), rate * 60.0))
-                    .collect();
+             let rates: HashMap<String, Rate> = target_items.iter().cloned().collect();
 
              // Score is now based on Density + Port Access Success
              let score = 100.0; 
@@ -326,12 +347,16 @@ impl LayoutGenerator {
                     facilities: layout,
                     score,
                     power_consumption,
-                    items_per_hour,
+                    rates,
                     efficiency: 1.0, // Assuming if placed, it works (idealized)
                     limiting_factor: None, 
+                    edges: Vec::new(),
              });
+        } else {
+            tracing::debug!("deterministic layout found no room for the required facilities");
         }
-        
+
+        tracing::debug!(candidates = candidates.len(), "layouts generated");
         candidates
     }
 }
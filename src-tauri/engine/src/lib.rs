@@ -0,0 +1,74 @@
+//! Simulation, solving and layout engine behind the Endfield sandbox. Has no Tauri
+//! dependency; the desktop app and `endfield-cli` are thin layers over it.
+//! Diagnostics go through `tracing`; binaries install a subscriber via `logging`.
+
+pub mod error;
+pub mod facility;
+pub mod item;
+pub mod recipe;
+pub mod grid;
+pub mod logistics;
+pub mod data_loader;
+/// GPU layout scoring; pulls in wgpu, so it's only built with the `gpu` feature
+#[cfg(feature = "gpu")]
+pub mod optimizer;
+pub mod power_grid;
+pub mod recipe_solver;
+pub mod layout_generator;
+pub mod logistics_engine;
+pub mod simulation_stats;
+pub mod verification;
+pub mod project;
+pub mod blueprint;
+pub mod grid_edit;
+pub mod history;
+pub mod geometry;
+pub mod edge_validation;
+pub mod plates;
+pub mod terrain;
+pub mod plan_export;
+pub mod rate;
+pub mod db_validation;
+pub mod live_simulation;
+pub mod sync;
+/// Log subscriber setup; only built with the `logging` feature
+#[cfg(feature = "logging")]
+pub mod logging;
+#[cfg(test)]
+pub mod recipe_solver_tests;
+#[cfg(test)]
+pub mod verification_tests;
+#[cfg(test)]
+pub mod logistics_engine_tests;
+#[cfg(test)]
+pub mod project_tests;
+#[cfg(test)]
+pub mod blueprint_tests;
+#[cfg(test)]
+pub mod grid_tests;
+#[cfg(test)]
+pub mod grid_edit_tests;
+#[cfg(test)]
+pub mod history_tests;
+#[cfg(test)]
+pub mod geometry_tests;
+#[cfg(test)]
+pub mod edge_validation_tests;
+#[cfg(test)]
+pub mod plates_tests;
+#[cfg(test)]
+pub mod terrain_tests;
+#[cfg(test)]
+pub mod plan_export_tests;
+#[cfg(test)]
+pub mod db_validation_tests;
+#[cfg(test)]
+pub mod live_simulation_tests;
+#[cfg(test)]
+pub mod error_tests;
+#[cfg(test)]
+pub mod sync_tests;
+#[cfg(test)]
+pub mod rate_tests;
+#[cfg(test)]
+pub mod simulation_stats_tests;
@@ -0,0 +1,176 @@
+use crate::facility::{BufferSlot, PlacedFacility};
+use crate::grid::GridState;
+use crate::plates::Plate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Ticks per second when config has no `simulation_tick_rate`
+pub const DEFAULT_TICK_RATE: f64 = 20.0;
+pub const MAX_TICK_RATE: f64 = 120.0;
+pub const MAX_SPEED: f64 = 16.0;
+
+/// Pacing of the background simulation loop. Each tick advances the simulation
+/// by `speed / tick_rate` seconds, so speed changes game time, not tick frequency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationClock {
+    pub paused: bool,
+    pub tick_rate: f64,
+    pub speed: f64,
+}
+
+impl SimulationClock {
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        let tick_rate = config["simulation_tick_rate"].as_f64()
+            .filter(|rate| *rate > 0.0)
+            .unwrap_or(DEFAULT_TICK_RATE)
+            .min(MAX_TICK_RATE);
+        Self { paused: false, tick_rate, speed: 1.0 }
+    }
+
+    /// Wall-clock time between ticks
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.tick_rate)
+    }
+
+    /// Simulated seconds per tick
+    pub fn step_seconds(&self) -> f64 {
+        self.speed / self.tick_rate
+    }
+
+    pub fn set_speed(&mut self, speed: f64) -> Result<(), String> {
+        if !(speed > 0.0 && speed <= MAX_SPEED) {
+            return Err(format!("Speed must be above 0 and at most {}, got {}", MAX_SPEED, speed));
+        }
+        self.speed = speed;
+        Ok(())
+    }
+
+    pub fn set_tick_rate(&mut self, tick_rate: f64) -> Result<(), String> {
+        if !(tick_rate > 0.0 && tick_rate <= MAX_TICK_RATE) {
+            return Err(format!("Tick rate must be above 0 and at most {}, got {}", MAX_TICK_RATE, tick_rate));
+        }
+        self.tick_rate = tick_rate;
+        Ok(())
+    }
+}
+
+/// Same shape as the `get_power_status` command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerStatus {
+    pub total_generation: f32,
+    pub total_consumption: f32,
+    pub power_balance: f32,
+    pub powered_count: usize,
+}
+
+impl PowerStatus {
+    pub fn of(grid: &GridState) -> Self {
+        Self {
+            total_generation: grid.power_grid.total_generation,
+            total_consumption: grid.power_grid.total_consumption,
+            power_balance: grid.power_grid.get_power_balance(),
+            powered_count: grid.power_grid.powered_facilities.len(),
+        }
+    }
+}
+
+/// The parts of a placed facility the simulation (or a command like `set_recipe_lock`) changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacilityUpdate {
+    pub instance_id: String,
+    pub active_recipe_id: Option<String>,
+    pub locked_recipe_id: Option<String>,
+    pub recipe_progress: f64,
+    pub recipe_remaining: f64,
+    pub input_buffer: Vec<BufferSlot>,
+    pub output_buffer: Vec<BufferSlot>,
+}
+
+impl FacilityUpdate {
+    pub fn of(facility: &PlacedFacility) -> Self {
+        Self {
+            instance_id: facility.instance_id.clone(),
+            active_recipe_id: facility.active_recipe_id.clone(),
+            locked_recipe_id: facility.locked_recipe_id.clone(),
+            recipe_progress: facility.recipe_progress,
+            recipe_remaining: facility.recipe_remaining,
+            input_buffer: facility.input_buffer.clone(),
+            output_buffer: facility.output_buffer.clone(),
+        }
+    }
+}
+
+/// What changed on a plate since the previous snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationDelta {
+    pub plate: String,
+    /// Every facility is listed, e.g. after switching plates; drop anything not in it
+    pub full: bool,
+    pub facilities: Vec<FacilityUpdate>,
+    /// Instance ids that are gone since the previous snapshot
+    pub removed: Vec<String>,
+    /// `None` when unchanged
+    pub power: Option<PowerStatus>,
+}
+
+impl SimulationDelta {
+    pub fn is_empty(&self) -> bool {
+        !self.full && self.facilities.is_empty() && self.removed.is_empty() && self.power.is_none()
+    }
+}
+
+/// Last state pushed to the frontend, kept to work out what to send next
+#[derive(Debug, Clone)]
+pub struct SimulationSnapshot {
+    plate: String,
+    facilities: HashMap<String, FacilityUpdate>,
+    power: PowerStatus,
+}
+
+impl SimulationSnapshot {
+    pub fn capture(plate: &Plate) -> Self {
+        Self {
+            plate: plate.name.clone(),
+            facilities: plate.grid.placed_facilities.iter()
+                .map(|f| (f.instance_id.clone(), FacilityUpdate::of(f)))
+                .collect(),
+            power: PowerStatus::of(&plate.grid),
+        }
+    }
+
+    /// Changes from `previous` to this snapshot; everything when there is no
+    /// previous snapshot of the same plate
+    pub fn delta_from(&self, previous: Option<&SimulationSnapshot>) -> SimulationDelta {
+        let Some(previous) = previous.filter(|p| p.plate == self.plate) else {
+            let mut facilities: Vec<FacilityUpdate> = self.facilities.values().cloned().collect();
+            facilities.sort_by(|a, b| a.instance_id.cmp(&b.instance_id));
+            return SimulationDelta {
+                plate: self.plate.clone(),
+                full: true,
+                facilities,
+                removed: Vec::new(),
+                power: Some(self.power.clone()),
+            };
+        };
+
+        let mut facilities: Vec<FacilityUpdate> = self.facilities.values()
+            .filter(|f| previous.facilities.get(&f.instance_id) != Some(*f))
+            .cloned()
+            .collect();
+        facilities.sort_by(|a, b| a.instance_id.cmp(&b.instance_id));
+        let mut removed: Vec<String> = previous.facilities.keys()
+            .filter(|id| !self.facilities.contains_key(*id))
+            .cloned()
+            .collect();
+        removed.sort();
+
+        SimulationDelta {
+            plate: self.plate.clone(),
+            full: false,
+            facilities,
+            removed,
+            power: (self.power != previous.power).then(|| self.power.clone()),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::data_loader::DataLoader;
+use crate::facility::{BufferSlot, PlacedFacility};
+use crate::grid::GridState;
+use crate::live_simulation::{SimulationClock, SimulationSnapshot, DEFAULT_TICK_RATE};
+use crate::plates::Plate;
+
+fn plate(name: &str) -> Plate {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    let facilities = vec![PlacedFacility::new("pac", "hub_pac_main", 0, 0), PlacedFacility::new("furnace", "item_port_furnance_1", 10, 0)];
+    grid.apply_layout(facilities, Vec::new(), &geometry).unwrap();
+    Plate::new(name, grid)
+}
+
+#[test]
+fn test_delta_lists_only_changes() {
+    let mut plate = plate("Main");
+    let first = SimulationSnapshot::capture(&plate);
+
+    // Nothing to compare against: everything is sent
+    let full = first.delta_from(None);
+    assert!(full.full);
+    assert_eq!(full.facilities.len(), 2);
+    assert!(full.power.is_some());
+
+    assert!(SimulationSnapshot::capture(&plate).delta_from(Some(&first)).is_empty());
+
+    plate.grid.placed_facilities[1].input_buffer[0] = BufferSlot {
+        item_id: "item_iron_ore".to_string(),
+        quantity: 5,
+        source_port_id: None,
+        target_port_id: None,
+    };
+    let second = SimulationSnapshot::capture(&plate);
+    let delta = second.delta_from(Some(&first));
+    assert!(!delta.full);
+    assert_eq!(delta.facilities.len(), 1);
+    assert_eq!(delta.facilities[0].instance_id, "furnace");
+    assert_eq!(delta.facilities[0].input_buffer[0].quantity, 5);
+    assert!(delta.power.is_none());
+
+    plate.grid.placed_facilities.remove(0);
+    let delta = SimulationSnapshot::capture(&plate).delta_from(Some(&second));
+    assert_eq!(delta.removed, vec!["pac".to_string()]);
+
+    // Switching plates resends the new plate in full
+    let other = SimulationSnapshot::capture(&self::plate("Outpost"));
+    assert!(other.delta_from(Some(&second)).full);
+}
+
+#[test]
+fn test_clock_speed_scales_step() {
+    let mut clock = SimulationClock::from_config(&serde_json::json!({}));
+    assert_eq!(clock.tick_rate, DEFAULT_TICK_RATE);
+    assert!((clock.step_seconds() - 0.05).abs() < 1e-9);
+
+    clock.set_speed(4.0).unwrap();
+    assert!((clock.step_seconds() - 0.2).abs() < 1e-9);
+    assert_eq!(clock.interval().as_millis(), 50);
+
+    assert!(clock.set_speed(0.0).is_err());
+    assert!(clock.set_speed(f64::NAN).is_err());
+    assert!(clock.set_tick_rate(-1.0).is_err());
+    assert_eq!(clock.speed, 4.0);
+}
+
+#[test]
+fn test_lock_survives_delta_and_resync() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut plate = plate("Main");
+    let before = SimulationSnapshot::capture(&plate);
+    plate.grid.placed_facilities[1].locked_recipe_id = Some("furnance_iron_nugget_1".to_string());
+
+    let delta = SimulationSnapshot::capture(&plate).delta_from(Some(&before));
+    assert_eq!(delta.facilities.len(), 1);
+    assert_eq!(delta.facilities[0].locked_recipe_id.as_deref(), Some("furnance_iron_nugget_1"));
+
+    // The frontend resyncs with the lock it took from the delta; the layout is replaced wholesale
+    let resync: Vec<PlacedFacility> = plate.grid.placed_facilities.iter()
+        .map(|f| {
+            let update = delta.facilities.iter().find(|u| u.instance_id == f.instance_id);
+            PlacedFacility {
+                locked_recipe_id: update.and_then(|u| u.locked_recipe_id.clone()),
+                ..PlacedFacility::new(&f.instance_id, &f.facility_id, f.x, f.y)
+            }
+        })
+        .collect();
+    plate.grid.apply_layout(resync, Vec::new(), &geometry).unwrap();
+    assert_eq!(plate.grid.placed_facilities[1].locked_recipe_id.as_deref(), Some("furnance_iron_nugget_1"));
+}
@@ -0,0 +1,119 @@
+//! Subscriber setup shared by the desktop app, `endfield-cli` and `endfield-server`.
+//! The engine itself only emits `tracing` events and spans; this wires them to stderr
+//! and to daily-rotated JSON log files that can be attached to bug reports.
+
+use crate::error::EngineError;
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+/// Environment variable holding a filter (`info,endfield_engine::recipe_solver=debug`);
+/// overrides the configured one
+pub const LOG_FILTER_VAR: &str = "ENDFIELD_LOG";
+pub const DEFAULT_FILTER: &str = "info";
+/// Daily files kept before the oldest is deleted
+pub const DEFAULT_MAX_LOG_FILES: usize = 7;
+
+#[derive(Debug, Clone)]
+pub struct LogOptions {
+    /// `EnvFilter` directives: a default level plus per-module overrides
+    pub filter: String,
+    /// Where rotated log files go; `None` disables the file log
+    pub directory: Option<PathBuf>,
+    /// File names are `<prefix>.<date>.log`
+    pub file_prefix: String,
+    pub max_files: usize,
+    pub stderr: bool,
+}
+
+impl LogOptions {
+    /// stderr only, at the default level
+    pub fn new(file_prefix: &str) -> Self {
+        Self {
+            filter: DEFAULT_FILTER.to_string(),
+            directory: None,
+            file_prefix: file_prefix.to_string(),
+            max_files: DEFAULT_MAX_LOG_FILES,
+            stderr: true,
+        }
+    }
+
+    /// Picks up `log_filter` and `log_max_files` from the database config
+    pub fn from_config(file_prefix: &str, config: &serde_json::Value) -> Self {
+        let mut options = Self::new(file_prefix);
+        if let Some(filter) = config["log_filter"].as_str() {
+            options.filter = filter.to_string();
+        }
+        if let Some(max_files) = config["log_max_files"].as_u64() {
+            options.max_files = max_files.max(1) as usize;
+        }
+        options
+    }
+}
+
+/// The installed subscriber. Keep it alive for the life of the process: dropping it
+/// stops the background writer and loses buffered file output.
+pub struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+    directory: Option<PathBuf>,
+    _file_guard: Option<WorkerGuard>,
+}
+
+impl Logging {
+    /// Installs the global subscriber. Fails if the filter doesn't parse, the log
+    /// directory can't be created, or a subscriber is already installed.
+    pub fn init(options: LogOptions) -> Result<Self, EngineError> {
+        let filter = std::env::var(LOG_FILTER_VAR).unwrap_or(options.filter);
+        let (filter, handle) = reload::Layer::new(parse_filter(&filter)?);
+
+        // Closing spans report their busy/idle time, which is how solve/layout/tick timings show up
+        let stderr = options.stderr.then(|| {
+            fmt::layer().with_writer(std::io::stderr).with_span_events(FmtSpan::CLOSE)
+        });
+        let (file, guard) = match &options.directory {
+            Some(directory) => {
+                std::fs::create_dir_all(directory)
+                    .map_err(|e| format!("Failed to create log directory {}: {}", directory.display(), e))?;
+                let appender = RollingFileAppender::builder()
+                    .rotation(Rotation::DAILY)
+                    .filename_prefix(&options.file_prefix)
+                    .filename_suffix("log")
+                    .max_log_files(options.max_files)
+                    .build(directory)
+                    .map_err(|e| format!("Failed to open log directory {}: {}", directory.display(), e))?;
+                let (writer, guard) = tracing_appender::non_blocking(appender);
+                let layer = fmt::layer().json().with_writer(writer).with_span_events(FmtSpan::CLOSE);
+                (Some(layer), Some(guard))
+            }
+            None => (None, None),
+        };
+
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(stderr)
+            .with(file)
+            .try_init()
+            .map_err(|e| format!("Failed to install logger: {}", e))?;
+
+        Ok(Self { filter: handle, directory: options.directory, _file_guard: guard })
+    }
+
+    /// Swaps the filter at runtime, e.g. to turn on `debug` for one module while reproducing a bug
+    pub fn set_filter(&self, filter: &str) -> Result<(), EngineError> {
+        let filter = parse_filter(filter)?;
+        self.filter.reload(filter).map_err(|e| format!("Failed to change log filter: {}", e).into())
+    }
+
+    /// Directory holding the log files, if file logging is on
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+}
+
+fn parse_filter(filter: &str) -> Result<EnvFilter, EngineError> {
+    EnvFilter::try_new(filter).map_err(|e| format!("Invalid log filter {:?}: {}", filter, e).into())
+}
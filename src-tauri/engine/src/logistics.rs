@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogisticsEdge {
+    pub from_instance_id: String,
+    pub from_port_id: String,
+    pub to_instance_id: String,
+    pub to_port_id: String,
+    pub item_id: String,
+    pub throughput: f32, // Items per second
+}
+
+/// Belt speed in items per second when config has no `logistics_flow_rate_units_per_s`
+pub const DEFAULT_THROUGHPUT: f32 = 0.5;
+
+impl LogisticsEdge {
+    /// Edge at the default belt speed; see `configured_throughput` for the configured one
+    pub fn new(from_instance_id: &str, from_port_id: &str, to_instance_id: &str, to_port_id: &str, item_id: &str) -> Self {
+        Self {
+            from_instance_id: from_instance_id.to_string(),
+            from_port_id: from_port_id.to_string(),
+            to_instance_id: to_instance_id.to_string(),
+            to_port_id: to_port_id.to_string(),
+            item_id: item_id.to_string(),
+            throughput: DEFAULT_THROUGHPUT,
+        }
+    }
+
+    /// Belt speed from config `logistics_flow_rate_units_per_s`, or `DEFAULT_THROUGHPUT`
+    pub fn configured_throughput(config: &serde_json::Value) -> f32 {
+        config["logistics_flow_rate_units_per_s"].as_f64()
+            .map(|rate| rate as f32)
+            .filter(|rate| *rate > 0.0)
+            .unwrap_or(DEFAULT_THROUGHPUT)
+    }
+
+    /// Stable identifier for an edge, used to key per-edge simulation state
+    pub fn key(&self) -> String {
+        format!(
+            "{}:{}->{}:{}",
+            self.from_instance_id, self.from_port_id, self.to_instance_id, self.to_port_id
+        )
+    }
+}
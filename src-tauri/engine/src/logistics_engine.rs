@@ -0,0 +1,389 @@
+use crate::error::EngineError;
+use crate::grid::GridState;
+use crate::facility::{BufferSlot, PlacedFacility};
+use crate::logistics::LogisticsEdge;
+use crate::recipe::{Recipe, RecipeIngredient, AMOUNT_SCALE};
+use crate::simulation_stats::FacilityStatus;
+use std::collections::{HashMap, HashSet};
+
+pub struct LogisticsEngine;
+
+/// Absorbs float drift when counting down crafting times
+const TIME_EPSILON: f64 = 1e-9;
+
+impl LogisticsEngine {
+    /// Deterministic step: advances the sim clock by `dt` seconds, moves items along edges,
+    /// then runs every facility.
+    #[tracing::instrument(level = "trace", name = "tick", skip_all, fields(dt, facilities = grid.placed_facilities.len(), edges = grid.logistics_edges.len()))]
+    pub fn step(grid: &mut GridState, recipes: &[Recipe], dt: f64) {
+        grid.sim_time += dt;
+        grid.stats.begin_step(grid.sim_time, dt);
+
+        // Items each producer's input slots take in: ingredients of its locked recipe, or of any
+        // recipe for its type. Facilities without recipes aren't listed and take anything.
+        let accepted_inputs: HashMap<String, Vec<HashSet<&str>>> = grid.placed_facilities.iter()
+            .filter_map(|f| Some((f.instance_id.clone(), f.slot_accepted_items(recipes)?)))
+            .collect();
+
+        Self::transfer_items(grid, &accepted_inputs, dt);
+
+        let GridState {
+            placed_facilities, logistics_edges, power_grid, stats, provider_ids, slot_capacity,
+            miner_ids, extraction_rate, mined_items, ..
+        } = grid;
+        let capacity = *slot_capacity;
+        let fed: HashSet<&str> = logistics_edges.iter().map(|e| e.to_instance_id.as_str()).collect();
+
+        // 4. Internal Processing (Machines & Facilities)
+        for facility in placed_facilities.iter_mut() {
+            if provider_ids.contains(&facility.facility_id) { continue; }
+
+            // Miners fill their output from the resource node they stand on
+            if miner_ids.contains(&facility.facility_id) {
+                let status = if power_grid.is_unpowered(&facility.instance_id) {
+                    FacilityStatus::Unpowered
+                } else if let Some(item_id) = mined_items.get(&facility.instance_id) {
+                    match Self::extract(facility, item_id, *extraction_rate * dt, capacity) {
+                        Some(count) => {
+                            if count > 0 {
+                                stats.record_extraction(facility, item_id, count);
+                            }
+                            FacilityStatus::Crafting
+                        }
+                        None => FacilityStatus::OutputBlocked,
+                    }
+                } else {
+                    FacilityStatus::Idle
+                };
+                stats.record_status(facility, status, dt);
+                continue;
+            }
+
+            // Belts, splitters etc. just hand items through
+            if !recipes.iter().any(|r| r.facility_id == facility.facility_id) {
+                Self::pass_through(facility, capacity);
+                continue;
+            }
+
+            let facility_recipes: Vec<&Recipe> = recipes.iter()
+                .filter(|r| facility.can_run(r))
+                .collect();
+
+            if power_grid.is_unpowered(&facility.instance_id) {
+                stats.record_status(facility, FacilityStatus::Unpowered, dt);
+                continue;
+            }
+
+            let mut blocked_by_output = false;
+
+            // --- 6 & 7. Timer & Produksi ---
+            // Finish first so the next craft can start in the same step without losing time
+            let mut completed = false;
+            if let Some(recipe_id) = facility.active_recipe_id.clone() {
+                if let Some(recipe) = recipes.iter().find(|r| r.id == recipe_id) {
+                    // Step 6: Timer berjalan (/s)
+                    facility.recipe_remaining = (facility.recipe_remaining - dt).max(0.0);
+                    facility.recipe_progress = Self::progress(recipe, facility.recipe_remaining);
+
+                    if facility.recipe_remaining <= TIME_EPSILON {
+                        // Step 7: Timer Selesai -> Munculkan item hasil ke slot out
+                        // Hold the finished craft while the output slots are full
+                        if Self::outputs_fit(facility, &recipe.outputs, capacity) {
+                            for output in &recipe.outputs {
+                                let whole = Self::carry_output(&mut facility.output_carry, output);
+                                if whole > 0 {
+                                    Self::deposit(&mut facility.output_buffer, &output.item_id, whole, capacity, None);
+                                }
+                            }
+                            stats.record_craft(facility, recipe);
+                            completed = true;
+
+                            // Reset untuk resep berikutnya
+                            facility.active_recipe_id = None;
+                            facility.recipe_progress = 0.0;
+                            facility.recipe_remaining = 0.0;
+                        } else {
+                            blocked_by_output = true;
+                        }
+                    }
+                }
+            }
+
+            // --- 1, 2, 3. Detection, Matching & Output Safety Check ---
+            if facility.active_recipe_id.is_none() {
+                for recipe in &facility_recipes {
+                    // Step 1 & 2: Check inputs (Jenis & Jumlah)
+                    let input_satisfied = recipe.inputs.iter().all(|input| {
+                        let in_buffer: u64 = facility.input_buffer.iter()
+                            .filter(|s| s.item_id == input.item_id)
+                            .map(|s| s.quantity as u64)
+                            .sum();
+                        let credit = facility.input_credit.get(&input.item_id).copied().unwrap_or(0);
+                        in_buffer * AMOUNT_SCALE + credit >= input.scaled_amount()
+                    });
+                    if !input_satisfied { continue; }
+
+                    // Step 3: Safety Check Slot Out
+                    // Block jika slot out tidak punya ruang untuk hasil resep
+                    if !Self::outputs_fit(facility, &recipe.outputs, capacity) {
+                        blocked_by_output = true;
+                        continue;
+                    }
+
+                    // Step 4: Konsumsi Item
+                    // Whole items are taken; whatever a fractional amount leaves over is kept as credit
+                    for input in &recipe.inputs {
+                        let credit = facility.input_credit.entry(input.item_id.clone()).or_insert(0);
+                        let shortfall = input.scaled_amount().saturating_sub(*credit);
+                        let mut needed = shortfall.div_ceil(AMOUNT_SCALE) as u32;
+                        *credit = *credit + needed as u64 * AMOUNT_SCALE - input.scaled_amount();
+
+                        while needed > 0 {
+                            if let Some(pos) = facility.input_buffer.iter().position(|s| s.item_id == input.item_id && s.quantity > 0) {
+                                let slot = &mut facility.input_buffer[pos];
+                                let taken = slot.quantity.min(needed);
+                                slot.quantity -= taken;
+                                needed -= taken;
+                                if slot.is_empty() {
+                                    slot.clear();
+                                }
+                            } else { break; }
+                        }
+                    }
+
+                    // Step 5: Timer Dimulai
+                    facility.active_recipe_id = Some(recipe.id.clone());
+                    facility.recipe_remaining = recipe.crafting_time.max(0.0) as f64;
+                    facility.recipe_progress = Self::progress(recipe, facility.recipe_remaining);
+                    blocked_by_output = false;
+                    break;
+                }
+            }
+
+            let status = if blocked_by_output {
+                FacilityStatus::OutputBlocked
+            } else if completed || facility.active_recipe_id.is_some() {
+                FacilityStatus::Crafting
+            } else if fed.contains(facility.instance_id.as_str()) || facility.input_buffer.iter().any(|s| !s.is_empty()) {
+                FacilityStatus::Starved
+            } else {
+                FacilityStatus::Idle
+            };
+            stats.record_status(facility, status, dt);
+        }
+
+    }
+
+    /// Moves whole items from each edge's source output buffer into its target input buffer,
+    /// at most `throughput` items per second.
+    fn transfer_items(grid: &mut GridState, accepted_inputs: &HashMap<String, Vec<HashSet<&str>>>, dt: f64) {
+        let GridState { placed_facilities, logistics_edges, edge_progress, stats, provider_ids, slot_capacity, .. } = grid;
+        let capacity = *slot_capacity;
+        let index: HashMap<String, usize> = placed_facilities.iter().enumerate()
+            .map(|(i, f)| (f.instance_id.clone(), i))
+            .collect();
+
+        for edge in logistics_edges.iter() {
+            let (Some(&from), Some(&to)) = (index.get(&edge.from_instance_id), index.get(&edge.to_instance_id)) else {
+                continue;
+            };
+            if from == to { continue; }
+
+            let key = edge.key();
+            let credit = edge_progress.entry(key.clone()).or_insert(0.0);
+            *credit += edge.throughput.max(0.0) as f64 * dt;
+
+            // Providers hand out their configured item without limit and store whatever they receive
+            let unlimited = provider_ids.contains(&placed_facilities[from].facility_id);
+            let depot = provider_ids.contains(&placed_facilities[to].facility_id);
+            let accepts = accepted_inputs.get(&placed_facilities[to].instance_id).map(Vec::as_slice);
+
+            let mut moved = 0;
+            while *credit >= 1.0 {
+                let filter = Self::edge_filter(&placed_facilities[from], edge);
+                let (item_id, pos) = if unlimited {
+                    let Some(item) = filter else { break };
+                    (item.to_string(), None)
+                } else {
+                    let Some(pos) = placed_facilities[from].output_buffer.iter()
+                        .position(|s| !s.is_empty() && filter.is_none_or(|item| s.item_id == item)) else {
+                        break;
+                    };
+                    (placed_facilities[from].output_buffer[pos].item_id.clone(), Some(pos))
+                };
+
+                if !depot {
+                    // Producers only take ingredients of the recipes they may run, each in its own slot
+                    let target = &mut placed_facilities[to].input_buffer;
+                    let Some(slot_pos) = Self::deposit(target, &item_id, 1, capacity, accepts) else { break };
+                    target[slot_pos].target_port_id = Some(edge.to_port_id.clone());
+                }
+
+                if let Some(pos) = pos {
+                    let slot = &mut placed_facilities[from].output_buffer[pos];
+                    slot.quantity -= 1;
+                    if slot.is_empty() {
+                        slot.clear();
+                    }
+                }
+
+                *credit -= 1.0;
+                moved += 1;
+            }
+            // Don't bank credit while the edge is starved or blocked
+            *credit = credit.min(1.0);
+
+            if moved > 0 {
+                stats.record_transfer(&key, moved);
+            }
+        }
+    }
+
+    /// Item restriction on an edge: the source port setting wins, then the edge's own item.
+    /// The frontend sends "placeholder" for edges that carry anything.
+    pub(crate) fn edge_filter<'a>(source: &'a PlacedFacility, edge: &'a LogisticsEdge) -> Option<&'a str> {
+        let port_setting = source.port_settings.as_ref()
+            .and_then(|settings| settings.iter().find(|s| s.port_id == edge.from_port_id))
+            .map(|s| s.item_id.as_str())
+            .filter(|item| !item.is_empty());
+
+        port_setting.or_else(|| {
+            let item = edge.item_id.as_str();
+            if item.is_empty() || item == "placeholder" { None } else { Some(item) }
+        })
+    }
+
+    /// Slot that would take `amount` of `item_id`: the slot already holding it, else an empty one.
+    /// One slot per item type, so a flood of one ingredient can't lock the others out.
+    ///
+    /// `accepts` lists the items each slot takes (see `PlacedFacility::slot_accepted_items`);
+    /// `None` lets any slot take anything, as for output buffers and facilities without recipes.
+    /// `inject` keeps direct edits within the same rules.
+    fn slot_for(buffer: &[BufferSlot], item_id: &str, amount: u32, capacity: u32, accepts: Option<&[HashSet<&str>]>) -> Option<usize> {
+        let takes = |pos: usize| accepts.is_none_or(|slots| slots.get(pos).is_some_and(|items| items.contains(item_id)));
+        match buffer.iter().position(|s| !s.is_empty() && s.item_id == item_id) {
+            Some(pos) => (takes(pos) && buffer[pos].quantity + amount <= capacity).then_some(pos),
+            None => (0..buffer.len()).find(|&pos| buffer[pos].is_empty() && takes(pos)).filter(|_| amount <= capacity),
+        }
+    }
+
+    /// Fills input slot `slot_index` with `quantity` (capped at `capacity`) of `item_id`,
+    /// replacing what it held. Rejects items the facility's recipes don't take, items bound
+    /// to another slot and items already held by another slot, which the transfer step
+    /// would never produce.
+    pub fn inject(facility: &mut PlacedFacility, slot_index: usize, item_id: &str, quantity: u32, capacity: u32, recipes: &[Recipe]) -> Result<u32, EngineError> {
+        if slot_index >= facility.input_buffer.len() {
+            return Err(EngineError::InvalidSlot {
+                instance_id: facility.instance_id.clone(),
+                slot_index,
+                slot_count: facility.input_buffer.len(),
+            });
+        }
+        if facility.accepted_items(recipes).is_some_and(|items| !items.contains(item_id)) {
+            return Err(EngineError::RejectedItem {
+                instance_id: facility.instance_id.clone(),
+                slot_index,
+                item_id: item_id.to_string(),
+                reason: "no recipe the facility may run takes it".to_string(),
+            });
+        }
+        if facility.slot_accepted_items(recipes).is_some_and(|slots| !slots[slot_index].contains(item_id)) {
+            return Err(EngineError::RejectedItem {
+                instance_id: facility.instance_id.clone(),
+                slot_index,
+                item_id: item_id.to_string(),
+                reason: "the facility's recipes take it in another slot".to_string(),
+            });
+        }
+        let held_elsewhere = facility.input_buffer.iter().enumerate()
+            .find(|(i, s)| *i != slot_index && !s.is_empty() && s.item_id == item_id);
+        if let Some((other, _)) = held_elsewhere {
+            return Err(EngineError::RejectedItem {
+                instance_id: facility.instance_id.clone(),
+                slot_index,
+                item_id: item_id.to_string(),
+                reason: format!("slot {} already holds it; each item type keeps to one slot", other),
+            });
+        }
+
+        let quantity = quantity.min(capacity);
+        facility.input_buffer[slot_index] = BufferSlot {
+            item_id: item_id.to_string(),
+            quantity,
+            source_port_id: None,
+            target_port_id: None,
+        };
+        Ok(quantity)
+    }
+
+    /// Adds `amount` to the slot picked by `slot_for` and returns its index; `None` when there is no room
+    pub(crate) fn deposit(buffer: &mut [BufferSlot], item_id: &str, amount: u32, capacity: u32, accepts: Option<&[HashSet<&str>]>) -> Option<usize> {
+        let pos = Self::slot_for(buffer, item_id, amount, capacity, accepts)?;
+        let slot = &mut buffer[pos];
+        if slot.is_empty() {
+            slot.item_id = item_id.to_string();
+        }
+        slot.quantity += amount;
+        Some(pos)
+    }
+
+    /// Whether a finished craft's whole output items fit the output slots
+    fn outputs_fit(facility: &PlacedFacility, outputs: &[RecipeIngredient], capacity: u32) -> bool {
+        let mut trial = facility.output_buffer.to_vec();
+        let mut carry = facility.output_carry.clone();
+        outputs.iter().all(|out| {
+            let whole = Self::carry_output(&mut carry, out);
+            whole == 0 || Self::deposit(&mut trial, &out.item_id, whole, capacity, None).is_some()
+        })
+    }
+
+    /// Adds one craft's worth of `output` to the carry and returns the whole items it completes
+    fn carry_output(carry: &mut HashMap<String, u64>, output: &RecipeIngredient) -> u32 {
+        let total = carry.entry(output.item_id.clone()).or_insert(0);
+        *total += output.scaled_amount();
+        let whole = *total / AMOUNT_SCALE;
+        *total %= AMOUNT_SCALE;
+        whole as u32
+    }
+
+    /// Share of `recipe` completed with `remaining` seconds to go
+    fn progress(recipe: &Recipe, remaining: f64) -> f64 {
+        let total = recipe.crafting_time as f64;
+        if total <= 0.0 { 1.0 } else { (1.0 - remaining / total).clamp(0.0, 1.0) }
+    }
+
+    /// Adds `amount` items of `item_id` to a miner's extraction carry and moves the whole
+    /// items into its output buffer. Returns how many were moved, or `None` if they don't
+    /// fit; the carry then stops growing until there is room.
+    fn extract(facility: &mut PlacedFacility, item_id: &str, amount: f64, capacity: u32) -> Option<u32> {
+        let carry = facility.output_carry.entry(item_id.to_string()).or_insert(0);
+        *carry += (amount.max(0.0) * AMOUNT_SCALE as f64).round() as u64;
+        let whole = (*carry / AMOUNT_SCALE) as u32;
+        if whole > 0 && Self::deposit(&mut facility.output_buffer, item_id, whole, capacity, None).is_none() {
+            *carry = (*carry).min(AMOUNT_SCALE);
+            return None;
+        }
+        *carry %= AMOUNT_SCALE;
+        Some(whole)
+    }
+
+    /// Facilities without recipes forward whatever they receive
+    fn pass_through(facility: &mut PlacedFacility, capacity: u32) {
+        let PlacedFacility { input_buffer, output_buffer, .. } = facility;
+        for slot in input_buffer.iter_mut().filter(|s| !s.is_empty()) {
+            let room = match output_buffer.iter().position(|s| !s.is_empty() && s.item_id == slot.item_id) {
+                Some(pos) => capacity.saturating_sub(output_buffer[pos].quantity),
+                None if output_buffer.iter().any(|s| s.is_empty()) => capacity,
+                None => 0,
+            };
+            let amount = slot.quantity.min(room);
+            if amount == 0 { continue; }
+
+            Self::deposit(output_buffer, &slot.item_id, amount, capacity, None);
+            slot.quantity -= amount;
+            if slot.is_empty() {
+                slot.clear();
+            }
+        }
+    }
+}
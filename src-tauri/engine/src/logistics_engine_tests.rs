@@ -0,0 +1,168 @@
+use crate::data_loader::DataLoader;
+use crate::facility::{BufferSlot, PlacedFacility};
+use crate::grid::GridState;
+use crate::logistics::LogisticsEdge;
+use crate::logistics_engine::LogisticsEngine;
+use crate::recipe::{Recipe, RecipeIngredient};
+
+const DT: f64 = 0.05;
+
+/// PAC feeding a Refining Unit whose output goes nowhere
+fn undrained_furnace(supply: &str) -> GridState {
+    let config = serde_json::json!({ "slot_capacity": 5 });
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&config, 32, 32);
+    grid.placed_facilities = vec![
+        PlacedFacility::new("pac", "hub_pac_main", 0, 0),
+        PlacedFacility::new("furnace", "item_port_furnance_1", 10, 0),
+    ];
+    grid.logistics_edges = vec![LogisticsEdge { throughput: 10.0, ..LogisticsEdge::new("pac", "out_r1", "furnace", "in_1", supply) }];
+    grid.resize_buffers(&geometry);
+    grid.update_power_grid(&geometry);
+    grid
+}
+
+fn run(grid: &mut GridState, seconds: f64) {
+    let recipes = DataLoader::load_recipes().unwrap();
+    for _ in 0..(seconds / DT).round() as usize {
+        LogisticsEngine::step(grid, &recipes, DT);
+    }
+}
+
+#[test]
+fn test_buffers_sized_from_slot_counts() {
+    let grid = undrained_furnace("item_iron_ore");
+    let furnace = &grid.placed_facilities[1];
+
+    // Refining Unit: 2 input slots, 1 output slot
+    assert_eq!(furnace.input_buffer.len(), 2);
+    assert_eq!(furnace.output_buffer.len(), 1);
+}
+
+#[test]
+fn test_crafting_blocks_when_output_full() {
+    let mut grid = undrained_furnace("item_iron_ore");
+    run(&mut grid, 60.0);
+
+    let furnace = &grid.placed_facilities[1];
+    assert_eq!(furnace.output_buffer[0].item_id, "item_iron_nugget");
+    assert_eq!(furnace.output_buffer[0].quantity, 5);
+    // Input stack capped too, and only one slot used for the single ingredient
+    assert_eq!(furnace.input_buffer[0].quantity, 5);
+    assert!(furnace.input_buffer[1].is_empty());
+
+    let recipes = DataLoader::load_recipes().unwrap();
+    let report = grid.stats.report(&grid.placed_facilities, &grid.logistics_edges, &recipes, None);
+    assert!(report.facilities[0].output_blocked_pct > 50.0);
+}
+
+#[test]
+fn test_rejects_items_no_recipe_uses() {
+    let mut grid = undrained_furnace("item_iron_cmpt");
+    run(&mut grid, 10.0);
+
+    let furnace = &grid.placed_facilities[1];
+    assert!(furnace.input_buffer.iter().all(|s| s.is_empty()));
+}
+
+#[test]
+fn test_fractional_amounts_are_exact() {
+    let recipes = vec![Recipe {
+        id: "half_ore".to_string(),
+        name: None,
+        inputs: vec![RecipeIngredient { item_id: "item_iron_ore".to_string(), amount: 0.5 }],
+        outputs: vec![RecipeIngredient { item_id: "item_iron_nugget".to_string(), amount: 1.5 }],
+        crafting_time: 1.0,
+        facility_id: "item_port_furnance_1".to_string(),
+    }];
+    let mut grid = undrained_furnace("item_iron_ore");
+    grid.logistics_edges.clear();
+    grid.slot_capacity = 50;
+    grid.placed_facilities[1].input_buffer[0] = BufferSlot {
+        item_id: "item_iron_ore".to_string(),
+        source_port_id: None,
+        target_port_id: None,
+        quantity: 2,
+    };
+
+    // Mid-craft (one step to start, ten to run): progress is a fraction of the recipe time
+    for _ in 0..11 {
+        LogisticsEngine::step(&mut grid, &recipes, DT);
+    }
+    let furnace = &grid.placed_facilities[1];
+    assert!((furnace.recipe_progress - 0.5).abs() < 1e-6);
+    assert!((furnace.recipe_remaining - 0.5).abs() < 1e-6);
+
+    // 2 ore -> 4 crafts -> 6 nuggets, nothing lost to rounding
+    for _ in 0..200 {
+        LogisticsEngine::step(&mut grid, &recipes, DT);
+    }
+    let furnace = &grid.placed_facilities[1];
+    assert!(furnace.active_recipe_id.is_none());
+    assert!(furnace.input_buffer.iter().all(|s| s.is_empty()));
+    assert_eq!(furnace.output_buffer[0].quantity, 6);
+    assert_eq!(furnace.output_carry.get("item_iron_nugget").copied().unwrap_or(0), 0);
+    assert_eq!(furnace.input_credit.get("item_iron_ore").copied().unwrap_or(0), 0);
+}
+
+#[test]
+fn test_locked_recipe_rejects_other_inputs() {
+    let mut grid = undrained_furnace("item_iron_ore");
+    grid.placed_facilities[1].locked_recipe_id = Some("furnance_iron_nugget_2".to_string());
+    grid.placed_facilities[1].input_buffer[0] = BufferSlot {
+        item_id: "item_iron_ore".to_string(),
+        source_port_id: None,
+        target_port_id: None,
+        quantity: 3,
+    };
+    run(&mut grid, 10.0);
+
+    // Ore is not an ingredient of the locked recipe: nothing arrives, the preloaded stack sits unused
+    let furnace = &grid.placed_facilities[1];
+    assert_eq!(furnace.input_buffer[0].quantity, 3);
+    assert!(furnace.input_buffer[1].is_empty());
+    assert!(furnace.output_buffer[0].is_empty());
+
+    grid.placed_facilities[1].locked_recipe_id = None;
+    run(&mut grid, 10.0);
+    assert!(!grid.placed_facilities[1].output_buffer[0].is_empty());
+}
+
+#[test]
+fn test_slot_refuses_items_bound_to_another_slot() {
+    let recipes = DataLoader::load_recipes().unwrap();
+    let mut grid = undrained_furnace("item_iron_ore");
+    grid.logistics_edges.push(LogisticsEdge { throughput: 10.0, ..LogisticsEdge::new("pac", "out_r2", "furnace", "in_2", "item_iron_powder") });
+
+    // Every furnace recipe has a single ingredient, so all of them go in slot 0. Ore and
+    // powder take turns there; neither spills into the free second slot.
+    for _ in 0..200 {
+        LogisticsEngine::step(&mut grid, &recipes, DT);
+        assert!(grid.placed_facilities[1].input_buffer[1].is_empty());
+    }
+    let furnace = &mut grid.placed_facilities[1];
+    assert!(!furnace.input_buffer[0].is_empty());
+    assert_eq!(LogisticsEngine::inject(furnace, 1, "item_iron_powder", 1, 5, &recipes).unwrap_err().code(), "rejected_item");
+
+    let slots = furnace.slot_accepted_items(&recipes).unwrap();
+    assert!(slots[0].contains("item_iron_ore") && slots[0].contains("item_iron_powder"));
+    assert!(slots[1].is_empty());
+}
+
+#[test]
+fn test_inject_keeps_one_slot_per_item() {
+    let recipes = DataLoader::load_recipes().unwrap();
+    let mut grid = undrained_furnace("item_iron_ore");
+    let furnace = &mut grid.placed_facilities[1];
+
+    assert_eq!(LogisticsEngine::inject(furnace, 0, "item_iron_ore", 9, 5, &recipes), Ok(5));
+    assert_eq!(furnace.input_buffer[0].quantity, 5);
+
+    // Same item in a second slot, an item no furnace recipe takes, a slot that doesn't exist
+    assert_eq!(LogisticsEngine::inject(furnace, 1, "item_iron_ore", 1, 5, &recipes).unwrap_err().code(), "rejected_item");
+    assert_eq!(LogisticsEngine::inject(furnace, 1, "item_iron_cmpt", 1, 5, &recipes).unwrap_err().code(), "rejected_item");
+    assert_eq!(LogisticsEngine::inject(furnace, 2, "item_iron_ore", 1, 5, &recipes).unwrap_err().code(), "invalid_slot");
+
+    // Refilling the slot that already holds it is fine
+    assert_eq!(LogisticsEngine::inject(furnace, 0, "item_iron_ore", 2, 5, &recipes), Ok(2));
+}
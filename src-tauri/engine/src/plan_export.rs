@@ -0,0 +1,128 @@
+use crate::recipe_solver::ProductionPlan;
+use crate::rate::Rate;
+use serde::{Deserialize, Serialize};
+
+/// Report formats a `ProductionPlan` can be rendered to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl ProductionPlan {
+    pub fn export(&self, format: ExportFormat) -> Result<String, String> {
+        match format {
+            ExportFormat::Csv => Ok(self.to_csv()),
+            ExportFormat::Markdown => Ok(self.to_markdown()),
+            ExportFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| format!("Failed to serialize plan: {}", e)),
+        }
+    }
+
+    /// One flat table; the `section` column tells targets, machines, raw materials and
+    /// totals apart so the file sorts and filters cleanly in a spreadsheet
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![["section", "id", "name", "recipe_id", "machines", "per_minute", "power", "area"]
+            .map(str::to_string)];
+        for (item_id, rate) in sorted(&self.actual_rates) {
+            rows.push(["target", item_id, "", "", "", &number(rate), "", ""].map(str::to_string));
+        }
+        for req in &self.required_facilities {
+            rows.push([
+                "machine", &req.facility_id, &req.facility_type, &req.recipe_id,
+                &number(req.count), "", &number(req.power), &number(req.area),
+            ].map(str::to_string));
+        }
+        for (item_id, rate) in sorted(&self.raw_materials) {
+            rows.push(["raw_material", item_id, "", "", "", &number(rate), "", ""].map(str::to_string));
+        }
+        rows.push(["total", "", "", "", "", "", &number(self.total_power), &number(self.total_area)].map(str::to_string));
+
+        rows.iter()
+            .map(|row| row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","))
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Production plan\n\n");
+        if let Some(reason) = self.limiting_factor.as_ref().filter(|_| self.constraint_limited) {
+            out += &format!("> Scaled down: {}\n\n", md_cell(reason));
+        }
+
+        out += "## Targets\n\n| Item | Per minute |\n|---|---:|\n";
+        for (item_id, rate) in sorted(&self.actual_rates) {
+            out += &format!("| {} | {} |\n", md_cell(item_id), number(rate));
+        }
+
+        out += "\n## Machines\n\n| Facility | Recipe | Machines | Power | Area |\n|---|---|---:|---:|---:|\n";
+        for req in &self.required_facilities {
+            out += &format!(
+                "| {} | {} | {} | {} | {} |\n",
+                md_cell(&req.facility_type), md_cell(&req.recipe_id), number(req.count), number(req.power), number(req.area),
+            );
+        }
+
+        out += "\n## Raw materials\n\n| Item | Per minute |\n|---|---:|\n";
+        for (item_id, rate) in sorted(&self.raw_materials) {
+            out += &format!("| {} | {} |\n", md_cell(item_id), number(rate));
+        }
+
+        if !self.port_bound.is_empty() {
+            out += "\n## Port-bound machines\n\n| Recipe | Port load | Extra machines for full output |\n|---|---:|---:|\n";
+            for bound in &self.port_bound {
+                out += &format!("| {} | {}x | {} |\n", md_cell(&bound.recipe_id), number(bound.port_utilization), number(bound.extra_machines));
+            }
+        }
+
+        let parallel: Vec<_> = self.parallel_belt_flows().collect();
+        if !parallel.is_empty() {
+            out += "\n## Parallel belts\n\n| Item | Per minute | Lanes |\n|---|---:|---:|\n";
+            for flow in parallel {
+                out += &format!("| {} | {} | {} |\n", md_cell(&flow.item_id), number(flow.rate.as_per_minute()), flow.lanes);
+            }
+        }
+
+        out += &format!("\n**Total power:** {}  \n**Total area:** {} cells\n", number(self.total_power), number(self.total_area));
+        out
+    }
+}
+
+/// Map entries ordered by key, so reports diff cleanly between runs. Rates are
+/// converted to per minute to match the report columns.
+fn sorted(map: &std::collections::HashMap<String, Rate>) -> Vec<(&str, f64)> {
+    let mut entries: Vec<(&str, f64)> = map.iter().map(|(k, v)| (k.as_str(), v.as_per_minute())).collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Two decimals, without trailing zeros
+fn number(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
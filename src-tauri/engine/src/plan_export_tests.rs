@@ -0,0 +1,89 @@
+use crate::facility::Facility;
+use crate::plan_export::ExportFormat;
+use crate::rate::Rate;
+use crate::recipe_solver::{ProductionPlan, Recipe, RecipeInput, RecipeOutput, RecipeSolver};
+use std::collections::HashMap;
+
+fn smelter(id: &str, name: &str) -> Facility {
+    Facility {
+        id: id.to_string(),
+        name: name.to_string(),
+        width: 3,
+        height: 3,
+        power_consumption: 10.0,
+        tier: 1,
+        icon: None,
+        category: None,
+        ports: None,
+        input_slots: None,
+        output_slots: None,
+        throughput_limit: None,
+        is_filter: None,
+    }
+}
+
+/// 60 plates/min from ore: 2 smelters (ore -> ingot, 30/min each) and 1 press
+/// (ingot -> plate, 60/min)
+fn plan() -> ProductionPlan {
+    let recipes = vec![
+        Recipe {
+            id: "ingot".to_string(),
+            name: None,
+            inputs: vec![RecipeInput { item_id: "ore".to_string(), amount: 1.0 }],
+            outputs: vec![RecipeOutput { item_id: "ingot".to_string(), amount: 1.0 }],
+            facility_id: "smelter".to_string(),
+            crafting_time: 2.0,
+        },
+        Recipe {
+            id: "plate".to_string(),
+            name: None,
+            inputs: vec![RecipeInput { item_id: "ingot".to_string(), amount: 1.0 }],
+            outputs: vec![RecipeOutput { item_id: "plate".to_string(), amount: 1.0 }],
+            facility_id: "press".to_string(),
+            crafting_time: 1.0,
+        },
+    ];
+    let mut facilities = HashMap::new();
+    facilities.insert("smelter".to_string(), smelter("smelter", "Smelter"));
+    facilities.insert("press".to_string(), smelter("press", "Press, \"Heavy\" | Mk1"));
+    RecipeSolver::new(recipes, facilities).solve(vec![("plate".to_string(), Rate::per_minute(60.0))], 100, 100).unwrap()
+}
+
+#[test]
+fn test_plan_tracks_power_and_area() {
+    let plan = plan();
+    let smelters = plan.required_facilities.iter().find(|r| r.recipe_id == "ingot").unwrap();
+    assert_eq!((smelters.count, smelters.power, smelters.area), (2.0, 20.0, 18.0));
+    assert_eq!(plan.total_power, 30.0);
+    assert_eq!(plan.total_area, 27.0);
+}
+
+#[test]
+fn test_csv_export() {
+    let csv = plan().export(ExportFormat::Csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "section,id,name,recipe_id,machines,per_minute,power,area");
+    assert_eq!(lines[1], "target,plate,,,,60,,");
+    assert!(lines.contains(&"machine,smelter,Smelter,ingot,2,,20,18"));
+    assert!(lines.contains(&"machine,press,\"Press, \"\"Heavy\"\" | Mk1\",plate,1,,10,9"));
+    assert!(lines.contains(&"raw_material,ore,,,,60,,"));
+    assert_eq!(*lines.last().unwrap(), "total,,,,,,30,27");
+}
+
+#[test]
+fn test_markdown_and_json_export() {
+    let plan = plan();
+    let markdown = plan.export(ExportFormat::Markdown).unwrap();
+    assert!(markdown.contains("| Smelter | ingot | 2 | 20 | 18 |"));
+    assert!(markdown.contains("| Press, \"Heavy\" \\| Mk1 | plate | 1 | 10 | 9 |"));
+    assert!(markdown.contains("| ore | 60 |"));
+    assert!(markdown.contains("## Parallel belts"));
+    assert!(markdown.contains("**Total area:** 27 cells"));
+    assert!(!markdown.contains("Scaled down"));
+
+    let json = plan.export(ExportFormat::Json).unwrap();
+    let parsed: ProductionPlan = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.total_area, plan.total_area);
+    assert_eq!(parsed.required_facilities.len(), plan.required_facilities.len());
+    assert_eq!(ExportFormat::Markdown.extension(), "md");
+}
@@ -0,0 +1,307 @@
+use crate::error::EngineError;
+use crate::grid::GridState;
+use crate::history::EditHistory;
+use crate::logistics_engine::LogisticsEngine;
+use crate::recipe::Recipe;
+use crate::simulation_stats::SimulationStats;
+use crate::terrain::PlateDefinition;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Name of the plate a fresh project starts with
+pub const DEFAULT_PLATE_NAME: &str = "Main";
+
+/// One named plate (outpost) with its own grid, power network, simulation and undo history
+#[derive(Debug)]
+pub struct Plate {
+    pub name: String,
+    pub grid: GridState,
+    pub history: EditHistory,
+}
+
+impl Plate {
+    pub fn new(name: &str, grid: GridState) -> Self {
+        Self { name: name.to_string(), grid, history: EditHistory::default() }
+    }
+}
+
+/// Ships items from a facility on one plate to a facility on another, like a depot
+/// loader/unloader pair. Same semantics as a `LogisticsEdge`: providers on the source
+/// side hand out `item_id` without limit, providers on the target side absorb anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlateLink {
+    pub from_plate: String,
+    pub from_instance_id: String,
+    pub to_plate: String,
+    pub to_instance_id: String,
+    /// Only this item is shipped; empty ships whatever the source outputs
+    #[serde(default)]
+    pub item_id: String,
+    pub throughput: f32, // Items per second
+}
+
+impl PlateLink {
+    /// Stable identifier, used to key per-link state
+    pub fn key(&self) -> String {
+        format!("{}/{}=>{}/{}", self.from_plate, self.from_instance_id, self.to_plate, self.to_instance_id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlateSummary {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub active: bool,
+    pub facility_count: usize,
+    pub power_balance: f32,
+    pub produced_per_minute: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkSummary {
+    pub key: String,
+    pub link: PlateLink,
+    pub items_per_minute: f64,
+}
+
+/// Production across every plate. Items shipped between plates count once, on the
+/// plate that made them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSummary {
+    pub plates: Vec<PlateSummary>,
+    pub links: Vec<LinkSummary>,
+    pub produced_per_minute: BTreeMap<String, f64>,
+    pub power_balance: f32,
+}
+
+/// All plates of a project. Edits go to the active plate; the simulation runs all of them.
+#[derive(Debug)]
+pub struct PlateSet {
+    pub plates: Vec<Plate>,
+    pub active: usize,
+    pub links: Vec<PlateLink>,
+    /// Fractional transfer credit per link (keyed by `PlateLink::key`)
+    pub link_progress: HashMap<String, f64>,
+    /// Shared clock for link transfers, advanced with the plates
+    pub sim_time: f64,
+    pub link_stats: SimulationStats,
+}
+
+impl PlateSet {
+    pub fn new(config: &serde_json::Value) -> Self {
+        Self::from_plates(vec![Plate::new(DEFAULT_PLATE_NAME, GridState::new(config))])
+    }
+
+    /// `plates` must not be empty
+    pub fn from_plates(plates: Vec<Plate>) -> Self {
+        Self {
+            plates,
+            active: 0,
+            links: Vec::new(),
+            link_progress: HashMap::new(),
+            sim_time: 0.0,
+            link_stats: SimulationStats::default(),
+        }
+    }
+
+    pub fn active(&self) -> &Plate {
+        &self.plates[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut Plate {
+        &mut self.plates[self.active]
+    }
+
+    fn index_of(&self, name: &str) -> Result<usize, EngineError> {
+        self.plates.iter().position(|p| p.name == name)
+            .ok_or_else(|| EngineError::MissingPlate { name: name.to_string() })
+    }
+
+    /// Width and height of a config preset, matched by id or name
+    pub fn preset_size(config: &serde_json::Value, preset: &str) -> Option<(u32, u32)> {
+        let preset = config["presets"].as_array()?.iter()
+            .find(|p| p["id"].as_str() == Some(preset) || p["name"].as_str() == Some(preset))?;
+        Some((preset["width"].as_u64()? as u32, preset["height"].as_u64()? as u32))
+    }
+
+    pub fn add_plate(&mut self, name: &str, width: u32, height: u32, config: &serde_json::Value) -> Result<(), EngineError> {
+        if name.trim().is_empty() {
+            return Err(EngineError::invalid("Plate name must not be empty"));
+        }
+        if self.index_of(name).is_ok() {
+            return Err(EngineError::Duplicate { kind: "plate", id: name.to_string() });
+        }
+        if width == 0 || height == 0 {
+            return Err(EngineError::invalid(format!("Plate size {}x{} is empty", width, height)));
+        }
+        self.plates.push(Plate::new(name, GridState::with_size(config, width, height)));
+        Ok(())
+    }
+
+    /// Adds a plate sized and shaped by a plate definition file
+    pub fn add_plate_from_definition(&mut self, definition: &PlateDefinition, config: &serde_json::Value) -> Result<(), EngineError> {
+        let terrain = definition.terrain().map_err(EngineError::invalid)?;
+        let (width, height) = definition.size();
+        self.add_plate(&definition.name, width, height, config)?;
+        self.plates.last_mut().unwrap().grid.terrain = terrain;
+        Ok(())
+    }
+
+    /// Removes the plate and every link touching it. The last plate can't be removed.
+    pub fn remove_plate(&mut self, name: &str) -> Result<(), EngineError> {
+        let index = self.index_of(name)?;
+        if self.plates.len() == 1 {
+            return Err(EngineError::invalid("A project needs at least one plate"));
+        }
+        self.plates.remove(index);
+        let (removed, kept) = std::mem::take(&mut self.links).into_iter()
+            .partition(|l: &PlateLink| l.from_plate == name || l.to_plate == name);
+        self.links = kept;
+        for link in removed {
+            self.link_progress.remove(&link.key());
+        }
+        if self.active > index || self.active == self.plates.len() {
+            self.active -= 1;
+        }
+        Ok(())
+    }
+
+    pub fn select(&mut self, name: &str) -> Result<(), EngineError> {
+        self.active = self.index_of(name)?;
+        Ok(())
+    }
+
+    pub fn add_link(&mut self, link: PlateLink) -> Result<(), EngineError> {
+        if link.from_plate == link.to_plate {
+            return Err(EngineError::invalid(format!("Link {} stays on one plate, use a logistics edge", link.key())));
+        }
+        for (plate, instance_id) in [(&link.from_plate, &link.from_instance_id), (&link.to_plate, &link.to_instance_id)] {
+            let grid = &self.plates[self.index_of(plate)?].grid;
+            if !grid.placed_facilities.iter().any(|f| f.instance_id == *instance_id) {
+                return Err(EngineError::missing_facility(instance_id.as_str()));
+            }
+        }
+        if self.links.iter().any(|l| l.key() == link.key()) {
+            return Err(EngineError::Duplicate { kind: "link", id: link.key() });
+        }
+        self.links.push(link);
+        Ok(())
+    }
+
+    pub fn remove_link(&mut self, key: &str) -> Result<PlateLink, EngineError> {
+        let index = self.links.iter().position(|l| l.key() == key)
+            .ok_or_else(|| EngineError::MissingLink { key: key.to_string() })?;
+        self.link_progress.remove(key);
+        Ok(self.links.remove(index))
+    }
+
+    /// Deterministic step: runs each plate's simulation for `dt` seconds, then moves
+    /// items along the links between them
+    #[tracing::instrument(level = "trace", name = "plates_step", skip_all, fields(dt, plates = self.plates.len()))]
+    pub fn step(&mut self, recipes: &[Recipe], dt: f64) {
+        for plate in &mut self.plates {
+            LogisticsEngine::step(&mut plate.grid, recipes, dt);
+        }
+        self.sim_time += dt;
+        self.link_stats.begin_step(self.sim_time, dt);
+        self.transfer_links(recipes, dt);
+    }
+
+    fn transfer_links(&mut self, recipes: &[Recipe], dt: f64) {
+        let PlateSet { plates, links, link_progress, link_stats, .. } = self;
+        for link in links.iter() {
+            let from_plate = plates.iter().position(|p| p.name == link.from_plate);
+            let to_plate = plates.iter().position(|p| p.name == link.to_plate);
+            let (Some(from_plate), Some(to_plate)) = (from_plate, to_plate) else {
+                continue;
+            };
+            let find = |plate: usize, instance_id: &str| {
+                plates[plate].grid.placed_facilities.iter().position(|f| f.instance_id == instance_id)
+            };
+            let (Some(from), Some(to)) = (find(from_plate, &link.from_instance_id), find(to_plate, &link.to_instance_id)) else {
+                continue;
+            };
+            let key = link.key();
+            let credit = link_progress.entry(key.clone()).or_insert(0.0);
+            *credit += link.throughput.max(0.0) as f64 * dt;
+
+            let mut moved = 0;
+            while *credit >= 1.0 {
+                let source_grid = &plates[from_plate].grid;
+                let source = &source_grid.placed_facilities[from];
+                let unlimited = source_grid.provider_ids.contains(&source.facility_id);
+                let filter = Some(link.item_id.as_str()).filter(|item| !item.is_empty());
+
+                let (item_id, pos) = if unlimited {
+                    let Some(item) = filter else { break };
+                    (item.to_string(), None)
+                } else {
+                    let Some(pos) = source.output_buffer.iter()
+                        .position(|s| !s.is_empty() && filter.is_none_or(|item| s.item_id == item)) else {
+                        break;
+                    };
+                    (source.output_buffer[pos].item_id.clone(), Some(pos))
+                };
+
+                let GridState { placed_facilities, provider_ids, slot_capacity, .. } = &mut plates[to_plate].grid;
+                let target = &mut placed_facilities[to];
+                // Providers store whatever they receive
+                if !provider_ids.contains(&target.facility_id) {
+                    let accepts = target.slot_accepted_items(recipes);
+                    if LogisticsEngine::deposit(&mut target.input_buffer, &item_id, 1, *slot_capacity, accepts.as_deref()).is_none() { break; }
+                }
+
+                if let Some(pos) = pos {
+                    let slot = &mut plates[from_plate].grid.placed_facilities[from].output_buffer[pos];
+                    slot.quantity -= 1;
+                    if slot.is_empty() {
+                        slot.clear();
+                    }
+                }
+
+                *credit -= 1.0;
+                moved += 1;
+            }
+            // Don't bank credit while the link is starved or blocked
+            *credit = credit.min(1.0);
+
+            if moved > 0 {
+                link_stats.record_transfer(&key, moved);
+            }
+        }
+    }
+
+    pub fn summary(&self) -> ProjectSummary {
+        let mut produced_per_minute: BTreeMap<String, f64> = BTreeMap::new();
+        let plates = self.plates.iter().enumerate().map(|(i, plate)| {
+            let produced: BTreeMap<String, f64> = plate.grid.stats.produced_per_minute().into_iter().collect();
+            for (item_id, rate) in &produced {
+                *produced_per_minute.entry(item_id.clone()).or_insert(0.0) += rate;
+            }
+            PlateSummary {
+                name: plate.name.clone(),
+                width: plate.grid.width,
+                height: plate.grid.height,
+                active: i == self.active,
+                facility_count: plate.grid.placed_facilities.len(),
+                power_balance: plate.grid.power_grid.get_power_balance(),
+                produced_per_minute: produced,
+            }
+        }).collect::<Vec<_>>();
+
+        let rates = self.link_stats.transfers_per_minute();
+        let links = self.links.iter().map(|link| LinkSummary {
+            key: link.key(),
+            link: link.clone(),
+            items_per_minute: rates.get(&link.key()).copied().unwrap_or(0.0),
+        }).collect();
+
+        ProjectSummary {
+            power_balance: plates.iter().map(|p| p.power_balance).sum(),
+            plates,
+            links,
+            produced_per_minute,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::data_loader::DataLoader;
+use crate::error::EngineError;
+use crate::facility::PlacedFacility;
+use crate::grid::GridState;
+use crate::plates::{Plate, PlateLink, PlateSet};
+
+fn link(from_plate: &str, from: &str, to_plate: &str, to: &str, item_id: &str) -> PlateLink {
+    PlateLink {
+        from_plate: from_plate.to_string(),
+        from_instance_id: from.to_string(),
+        to_plate: to_plate.to_string(),
+        to_instance_id: to.to_string(),
+        item_id: item_id.to_string(),
+        throughput: 1.0,
+    }
+}
+
+fn plate(name: &str, facilities: Vec<PlacedFacility>) -> Plate {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&DataLoader::load_config().unwrap(), 32, 32);
+    grid.apply_layout(facilities, Vec::new(), &geometry).unwrap();
+    Plate::new(name, grid)
+}
+
+/// A mine whose PAC ships ore to a smelter outpost, which ships nuggets back
+fn mine_and_smelter() -> PlateSet {
+    let mut furnace = PlacedFacility::new("furnace", "item_port_furnance_1", 10, 0);
+    furnace.locked_recipe_id = Some("furnance_iron_nugget_1".to_string());
+    let mut plates = PlateSet::from_plates(vec![
+        plate("Mine", vec![PlacedFacility::new("pac", "hub_pac_main", 0, 0)]),
+        plate("Smelter", vec![PlacedFacility::new("pac", "hub_pac_main", 0, 0), furnace]),
+    ]);
+    plates.add_link(link("Mine", "pac", "Smelter", "furnace", "item_iron_ore")).unwrap();
+    plates.add_link(link("Smelter", "furnace", "Mine", "pac", "")).unwrap();
+    plates
+}
+
+#[test]
+fn test_links_move_items_between_plates() {
+    let recipes = DataLoader::load_recipes().unwrap();
+    let mut plates = mine_and_smelter();
+    for _ in 0..400 {
+        plates.step(&recipes, 0.05);
+    }
+
+    // Every plate ran its own simulation
+    assert!(plates.plates.iter().all(|p| (p.grid.sim_time - 20.0).abs() < 1e-6));
+    let furnace = &plates.plates[1].grid.placed_facilities[1];
+    assert!(furnace.output_buffer.iter().all(|s| s.quantity <= 1), "nuggets are shipped out");
+
+    let summary = plates.summary();
+    let nuggets = summary.produced_per_minute["item_iron_nugget"];
+    assert!(nuggets > 20.0, "expected close to 30 nuggets/min, got {}", nuggets);
+    assert_eq!(summary.plates[1].produced_per_minute["item_iron_nugget"], nuggets);
+    assert!(summary.plates[0].produced_per_minute.is_empty());
+    assert!(summary.links.iter().all(|l| l.items_per_minute > 20.0));
+    assert!(summary.plates[0].active);
+}
+
+#[test]
+fn test_plate_management() {
+    let config = DataLoader::load_config().unwrap();
+    let mut plates = mine_and_smelter();
+
+    assert_eq!(PlateSet::preset_size(&config, "Small Outpost"), Some((32, 20)));
+    assert_eq!(PlateSet::preset_size(&config, "small"), Some((32, 20)));
+    plates.add_plate("Quarry", 32, 20, &config).unwrap();
+    assert_eq!(plates.add_plate("Mine", 32, 32, &config).unwrap_err().code(), "duplicate");
+
+    assert!(plates.add_link(link("Mine", "pac", "Mine", "pac", "")).is_err());
+    assert!(plates.add_link(link("Mine", "pac", "Quarry", "nothing", "")).is_err());
+    assert!(plates.add_link(link("Mine", "pac", "Smelter", "furnace", "item_iron_ore")).is_err());
+
+    assert_eq!(plates.select("Ghost").unwrap_err(), EngineError::MissingPlate { name: "Ghost".to_string() });
+    plates.select("Quarry").unwrap();
+    assert_eq!(plates.active().name, "Quarry");
+    plates.remove_plate("Smelter").unwrap();
+    assert!(plates.links.is_empty());
+    assert_eq!(plates.active().name, "Quarry");
+
+    plates.remove_plate("Quarry").unwrap();
+    assert_eq!(plates.active().name, "Mine");
+    assert!(plates.remove_plate("Mine").is_err());
+}
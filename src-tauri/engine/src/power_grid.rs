@@ -1,12 +1,15 @@
-use crate::engine::facility::PlacedFacility;
+use crate::facility::PlacedFacility;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PowerGrid {
     pub total_generation: f32,
     pub total_consumption: f32,
     pub powered_facilities: HashSet<String>,
+    /// Consumers that draw power but are outside every generator/pylon range
+    #[serde(default)]
+    pub unpowered_facilities: HashSet<String>,
 }
 
 impl PowerGrid {
@@ -15,6 +18,7 @@ impl PowerGrid {
             total_generation: 0.0,
             total_consumption: 0.0,
             powered_facilities: HashSet::new(),
+            unpowered_facilities: HashSet::new(),
         }
     }
 
@@ -29,6 +33,7 @@ impl PowerGrid {
         self.total_generation = 0.0;
         self.total_consumption = 0.0;
         self.powered_facilities.clear();
+        self.unpowered_facilities.clear();
 
         let empty_vec = vec![];
         let geom_array = geometry.as_array().unwrap_or(&empty_vec);
@@ -42,7 +47,11 @@ impl PowerGrid {
             if let Some(geom) = geom_array.iter().find(|g| {
                 g["id"].as_str().unwrap_or("") == facility.facility_id
             }) {
-                let power = geom["power"].as_f64().unwrap_or(0.0) as f32;
+                // Generators either use negative "power" or a dedicated "power_generation" field
+                let power = match geom["power_generation"].as_f64() {
+                    Some(generation) if generation > 0.0 => -generation as f32,
+                    _ => geom["power"].as_f64().unwrap_or(0.0) as f32,
+                };
 
                 if power < 0.0 {
                     // Generator (PAC, Thermal Bank)
@@ -95,17 +104,18 @@ impl PowerGrid {
             // Check pylon broadcast
             if !is_powered {
                 for pylon in &pylons {
-                    if active_pylons.contains(&pylon.instance_id) {
-                        if Self::manhattan_distance(consumer, pylon) <= pylon_range {
-                            is_powered = true;
-                            break;
-                        }
+                    if active_pylons.contains(&pylon.instance_id)
+                        && Self::manhattan_distance(consumer, pylon) <= pylon_range {
+                        is_powered = true;
+                        break;
                     }
                 }
             }
 
             if is_powered {
                 self.powered_facilities.insert(consumer.instance_id.clone());
+            } else {
+                self.unpowered_facilities.insert(consumer.instance_id.clone());
             }
         }
     }
@@ -118,6 +128,11 @@ impl PowerGrid {
         self.powered_facilities.contains(instance_id)
     }
 
+    /// True when the facility consumes power but no generator or pylon reaches it
+    pub fn is_unpowered(&self, instance_id: &str) -> bool {
+        self.unpowered_facilities.contains(instance_id)
+    }
+
     pub fn get_power_balance(&self) -> f32 {
         self.total_generation - self.total_consumption
     }
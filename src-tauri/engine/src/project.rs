@@ -0,0 +1,173 @@
+use crate::error::EngineError;
+use crate::facility::PlacedFacility;
+use crate::grid::GridState;
+use crate::logistics::LogisticsEdge;
+use crate::plates::{Plate, PlateLink, PlateSet, DEFAULT_PLATE_NAME};
+use crate::rate::Rate;
+use crate::terrain::Terrain;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Bump when the layout of `ProjectFile` changes and add a step to `ProjectFile::migrate`
+pub const PROJECT_VERSION: u32 = 2;
+
+/// One plate of a saved project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlateFile {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub placed_facilities: Vec<PlacedFacility>,
+    pub logistics_edges: Vec<LogisticsEdge>,
+    #[serde(default)]
+    pub terrain: Terrain,
+    #[serde(default)]
+    pub sim_time: f64,
+    #[serde(default)]
+    pub edge_progress: HashMap<String, f64>,
+}
+
+impl PlateFile {
+    pub fn capture(name: &str, grid: &GridState) -> Self {
+        Self {
+            name: name.to_string(),
+            width: grid.width,
+            height: grid.height,
+            placed_facilities: grid.placed_facilities.clone(),
+            logistics_edges: grid.logistics_edges.clone(),
+            terrain: grid.terrain.clone(),
+            sim_time: grid.sim_time,
+            edge_progress: grid.edge_progress.clone(),
+        }
+    }
+
+    /// Builds a live grid from the plate, rebuilding the state that isn't saved
+    /// (occupancy, mined resources, power grid, slot layout)
+    pub fn restore(&self, config: &serde_json::Value, geometry: &serde_json::Value) -> GridState {
+        let mut grid = GridState::with_size(config, self.width, self.height);
+        grid.placed_facilities = self.placed_facilities.clone();
+        grid.logistics_edges = self.logistics_edges.clone();
+        grid.terrain = self.terrain.clone();
+        grid.sim_time = self.sim_time;
+        grid.edge_progress = self.edge_progress.clone();
+        grid.resize_buffers(geometry);
+        grid.rebuild_occupancy(geometry);
+        grid.update_power_grid(geometry);
+        grid
+    }
+}
+
+/// Everything needed to restore a sandbox session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectFile {
+    pub version: u32,
+    pub plates: Vec<PlateFile>,
+    /// Index into `plates` of the plate being edited
+    #[serde(default)]
+    pub active_plate: usize,
+    #[serde(default)]
+    pub links: Vec<PlateLink>,
+    #[serde(default)]
+    pub link_progress: HashMap<String, f64>,
+    /// Production targets; bare numbers from older files read as per minute
+    #[serde(default)]
+    pub targets: Vec<(String, Rate)>,
+}
+
+impl ProjectFile {
+    pub fn capture(plates: &PlateSet, targets: &[(String, Rate)]) -> Self {
+        Self {
+            version: PROJECT_VERSION,
+            plates: plates.plates.iter().map(|p| PlateFile::capture(&p.name, &p.grid)).collect(),
+            active_plate: plates.active,
+            links: plates.links.clone(),
+            link_progress: plates.link_progress.clone(),
+            targets: targets.to_vec(),
+        }
+    }
+
+    /// Builds the live plates. Undo history starts empty.
+    pub fn restore(&self, config: &serde_json::Value, geometry: &serde_json::Value) -> Result<PlateSet, EngineError> {
+        self.validate()?;
+        let mut plates = PlateSet::from_plates(self.plates.iter()
+            .map(|p| Plate::new(&p.name, p.restore(config, geometry)))
+            .collect());
+        plates.active = self.active_plate.min(self.plates.len() - 1);
+        plates.links = self.links.clone();
+        plates.link_progress = self.link_progress.clone();
+        Ok(plates)
+    }
+
+    /// Plates are looked up by name, so there must be at least one and no two may share a name
+    fn validate(&self) -> Result<(), EngineError> {
+        if self.plates.is_empty() {
+            return Err(EngineError::invalid("Project file has no plates"));
+        }
+        let mut names = HashSet::new();
+        if let Some(plate) = self.plates.iter().find(|p| !names.insert(p.name.as_str())) {
+            return Err(EngineError::Duplicate { kind: "plate", id: plate.name.clone() });
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String, EngineError> {
+        serde_json::to_string_pretty(self).map_err(|e| EngineError::invalid(format!("Failed to serialize project: {}", e)))
+    }
+
+    /// Parses a project file of any known version
+    pub fn from_json(text: &str) -> Result<Self, EngineError> {
+        let value: serde_json::Value = serde_json::from_str(text)
+            .map_err(|e| EngineError::invalid(format!("Malformed project file: {}", e)))?;
+        let project: Self = serde_json::from_value(Self::migrate(value)?)
+            .map_err(|e| EngineError::invalid(format!("Invalid project file: {}", e)))?;
+        project.validate()?;
+        Ok(project)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), EngineError> {
+        fs::write(path, self.to_json()?).map_err(|e| EngineError::invalid(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, EngineError> {
+        let text = fs::read_to_string(path).map_err(|e| EngineError::invalid(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::from_json(&text)
+    }
+
+    /// Upgrades older files one version at a time
+    fn migrate(mut value: serde_json::Value) -> Result<serde_json::Value, EngineError> {
+        let version = value["version"].as_u64().unwrap_or(0) as u32;
+        if version > PROJECT_VERSION {
+            return Err(EngineError::invalid(format!("Project file version {} is newer than supported ({})", version, PROJECT_VERSION)));
+        }
+
+        // v0: raw `get_grid_state` dump. `recipe_progress` was the craft's start timestamp;
+        // with no time remaining, running crafts finish on the next step.
+        if version < 1 {
+            if let Some(facilities) = value["placed_facilities"].as_array_mut() {
+                for facility in facilities {
+                    if facility["recipe_progress"].as_f64().is_some_and(|p| p > 1.0) {
+                        facility["recipe_progress"] = serde_json::json!(0.0);
+                        facility["recipe_remaining"] = serde_json::json!(0.0);
+                    }
+                }
+            }
+            value["version"] = serde_json::json!(1);
+        }
+
+        // v1: a single plate stored at the top level
+        if version < 2 {
+            let mut plate = serde_json::json!({ "name": DEFAULT_PLATE_NAME });
+            for key in ["width", "height", "placed_facilities", "logistics_edges", "sim_time", "edge_progress"] {
+                if let Some(field) = value.as_object_mut().and_then(|o| o.remove(key)) {
+                    plate[key] = field;
+                }
+            }
+            value["plates"] = serde_json::json!([plate]);
+            value["version"] = serde_json::json!(2);
+        }
+
+        Ok(value)
+    }
+}
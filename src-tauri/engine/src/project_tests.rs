@@ -0,0 +1,110 @@
+use crate::data_loader::DataLoader;
+use crate::facility::PlacedFacility;
+use crate::grid::GridState;
+use crate::logistics::LogisticsEdge;
+use crate::logistics_engine::LogisticsEngine;
+use crate::plates::{Plate, PlateSet};
+use crate::project::{ProjectFile, PROJECT_VERSION};
+use crate::rate::Rate;
+
+fn running_grid() -> GridState {
+    let config = DataLoader::load_config().unwrap();
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut grid = GridState::with_size(&config, 24, 16);
+    grid.placed_facilities = vec![
+        PlacedFacility::new("pac", "hub_pac_main", 0, 0),
+        PlacedFacility::new("furnace", "item_port_furnance_1", 10, 0),
+    ];
+    grid.placed_facilities[1].locked_recipe_id = Some("furnance_iron_nugget_1".to_string());
+    grid.logistics_edges = vec![LogisticsEdge::new("pac", "out_r1", "furnace", "in_1", "item_iron_ore")];
+    grid.resize_buffers(&geometry);
+    grid.update_power_grid(&geometry);
+
+    let recipes = DataLoader::load_recipes().unwrap();
+    for _ in 0..100 {
+        LogisticsEngine::step(&mut grid, &recipes, 0.05);
+    }
+    grid
+}
+
+#[test]
+fn test_round_trip_restores_state() {
+    let grid = running_grid();
+    let targets = vec![("item_iron_nugget".to_string(), Rate::per_hour(1800.0))];
+
+    let plates = PlateSet::from_plates(vec![Plate::new("Main", running_grid()), Plate::new("Outpost", grid)]);
+    let text = ProjectFile::capture(&plates, &targets).to_json().unwrap();
+    let project = ProjectFile::from_json(&text).unwrap();
+    let restored = project.restore(&DataLoader::load_config().unwrap(), &DataLoader::load_geometry().unwrap()).unwrap();
+    assert_eq!(restored.plates.len(), 2);
+    assert_eq!(restored.plates[1].name, "Outpost");
+    let grid = &plates.plates[1].grid;
+    let restored = &restored.plates[1].grid;
+
+    assert_eq!(project.version, PROJECT_VERSION);
+    assert_eq!(project.targets, targets);
+    assert_eq!((restored.width, restored.height), (24, 16));
+    assert_eq!(restored.sim_time, grid.sim_time);
+    assert_eq!(restored.logistics_edges.len(), 1);
+
+    let furnace = &restored.placed_facilities[1];
+    assert_eq!(furnace.locked_recipe_id.as_deref(), Some("furnance_iron_nugget_1"));
+    assert_eq!(furnace.active_recipe_id, grid.placed_facilities[1].active_recipe_id);
+    assert_eq!(furnace.input_buffer[0].quantity, grid.placed_facilities[1].input_buffer[0].quantity);
+
+    // Rebuilt, not saved
+    assert!(!restored.is_area_clear(10, 0, 3, 3));
+    assert!(restored.is_area_clear(13, 0, 3, 3));
+    assert!(!restored.power_grid.is_unpowered("furnace"));
+}
+
+#[test]
+fn test_migrates_grid_state_dump() {
+    // What `get_grid_state` returned before project files existed
+    let old = serde_json::json!({
+        "width": 24,
+        "height": 16,
+        "placed_facilities": [{
+            "instance_id": "furnace",
+            "facility_id": "item_port_furnance_1",
+            "x": 10, "y": 0, "rotation": 0,
+            "port_settings": null,
+            "active_recipe_id": "furnance_iron_nugget_1",
+            "recipe_progress": 1767225600.0
+        }],
+        "logistics_edges": []
+    });
+
+    let project = ProjectFile::from_json(&old.to_string()).unwrap();
+
+    assert_eq!(project.version, PROJECT_VERSION);
+    assert!(project.targets.is_empty());
+    let plate = &project.plates[0];
+    assert_eq!((plate.name.as_str(), plate.width, plate.height), ("Main", 24, 16));
+    assert_eq!(plate.sim_time, 0.0);
+    let furnace = &plate.placed_facilities[0];
+    assert_eq!(furnace.recipe_progress, 0.0);
+    assert_eq!(furnace.active_recipe_id.as_deref(), Some("furnance_iron_nugget_1"));
+}
+
+#[test]
+fn test_rejects_newer_version() {
+    let newer = serde_json::json!({ "version": PROJECT_VERSION + 1 });
+    assert!(ProjectFile::from_json(&newer.to_string()).is_err());
+}
+
+#[test]
+fn test_restore_rejects_empty_and_duplicate_plates() {
+    let config = DataLoader::load_config().unwrap();
+    let geometry = DataLoader::load_geometry().unwrap();
+    let grid = GridState::with_size(&config, 8, 8);
+    let plates = PlateSet::from_plates(vec![Plate::new("Main", grid.clone()), Plate::new("Outpost", grid)]);
+    let mut project = ProjectFile::capture(&plates, &[]);
+
+    project.plates[1].name = "Main".to_string();
+    assert!(project.restore(&config, &geometry).is_err());
+    assert!(ProjectFile::from_json(&project.to_json().unwrap()).is_err());
+
+    project.plates.clear();
+    assert!(project.restore(&config, &geometry).is_err());
+}
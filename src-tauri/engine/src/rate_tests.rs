@@ -0,0 +1,31 @@
+use crate::rate::{Rate, RateUnit};
+
+#[test]
+fn test_converts_between_units() {
+    let rate = Rate::per_second(0.5);
+    assert_eq!(rate.as_per_minute(), 30.0);
+    assert_eq!(rate.as_per_hour(), 1800.0);
+    assert_eq!(Rate::per_hour(1800.0).to_unit(RateUnit::PerSecond), rate);
+    assert_eq!(rate.scaled(2.0), Rate::per_second(1.0));
+    assert_eq!(Rate::per_minute(30.0).ratio_of(Rate::per_hour(900.0)), Some(0.5));
+    assert_eq!(Rate::per_minute(0.0).ratio_of(rate), None);
+    assert_eq!(Rate::per_minute(30.0).to_string(), "30/min");
+}
+
+#[test]
+fn test_parses_numbers_strings_and_objects() {
+    let rates: Vec<Rate> = serde_json::from_str(r#"[30, "0.5/s", "1800 / h", { "amount": 2, "unit": "per_hour" }, { "amount": 4 }]"#).unwrap();
+    assert_eq!(rates, vec![
+        Rate::per_minute(30.0),
+        Rate::per_second(0.5),
+        Rate::per_hour(1800.0),
+        Rate::per_hour(2.0),
+        Rate::per_minute(4.0),
+    ]);
+    assert!("12/day".parse::<Rate>().is_err());
+    assert_eq!(serde_json::to_value(Rate::per_second(1.5)).unwrap(), serde_json::json!({ "amount": 1.5, "unit": "per_second" }));
+
+    // Projects saved before rates carried units stored bare per-minute numbers
+    let targets: Vec<(String, Rate)> = serde_json::from_str(r#"[["item_iron_nugget", 30.0]]"#).unwrap();
+    assert_eq!(targets, vec![("item_iron_nugget".to_string(), Rate::per_minute(30.0))]);
+}
@@ -1,11 +1,21 @@
 use serde::{Deserialize, Serialize};
 
+/// Recipe amounts are handled in thousandths of an item so fractional recipes stay exact
+pub const AMOUNT_SCALE: u64 = 1000;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RecipeIngredient {
     pub item_id: String,
     pub amount: f32,
 }
 
+impl RecipeIngredient {
+    /// `amount` in fixed-point `AMOUNT_SCALE` units
+    pub fn scaled_amount(&self) -> u64 {
+        (self.amount.max(0.0) as f64 * AMOUNT_SCALE as f64).round() as u64
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Recipe {
     pub id: String,
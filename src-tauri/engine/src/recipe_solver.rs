@@ -0,0 +1,482 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::data_loader::DataLoader;
+use crate::error::EngineError;
+use crate::layout_generator::{LayoutConstraints, LayoutGenerator};
+use crate::logistics::LogisticsEdge;
+use crate::rate::Rate;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeInput {
+    pub item_id: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeOutput {
+    pub item_id: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    pub id: String,
+    pub name: Option<String>, // Made optional to prevent crash if missing
+    pub inputs: Vec<RecipeInput>,
+    pub outputs: Vec<RecipeOutput>,
+    pub facility_id: String,
+    pub crafting_time: f64,
+}
+
+impl From<&crate::recipe::Recipe> for Recipe {
+    fn from(r: &crate::recipe::Recipe) -> Self {
+        Self {
+            id: r.id.clone(),
+            name: r.name.clone(),
+            inputs: r.inputs.iter().map(|i| RecipeInput {
+                item_id: i.item_id.clone(),
+                amount: i.amount as f64,
+            }).collect(),
+            outputs: r.outputs.iter().map(|o| RecipeOutput {
+                item_id: o.item_id.clone(),
+                amount: o.amount as f64,
+            }).collect(),
+            facility_id: r.facility_id.clone(),
+            crafting_time: r.crafting_time as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacilityRequirement {
+    pub facility_id: String,
+    pub facility_type: String, // Display Name
+    pub count: f64,
+    pub recipe_id: String,
+    /// Power drawn by all `count` machines
+    #[serde(default)]
+    pub power: f64,
+    /// Cells covered by all `count` machines
+    #[serde(default)]
+    pub area: f64,
+    /// Belt load one machine puts on its busiest port at full speed, as a share of one lane.
+    /// Each port carries one lane, of one item when there are enough ports. Above 1.0 the machine is port-bound and runs
+    /// at 1 / `port_utilization` of its speed. 0 when unknown.
+    #[serde(default)]
+    pub port_utilization: f64,
+}
+
+/// A recipe whose machines can't be fed (or emptied) at full speed through their ports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortBound {
+    pub recipe_id: String,
+    pub port_utilization: f64,
+    /// Machines on top of the requirement's `count` that would run the recipe at full output
+    pub extra_machines: f64,
+}
+
+/// One item's flow through the plan and the belt lanes it takes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeltFlow {
+    pub item_id: String,
+    pub rate: Rate,
+    pub lanes: u32,
+}
+
+impl BeltFlow {
+    /// More than one lane: the flow has to be split over parallel belts
+    pub fn needs_parallel_belts(&self) -> bool {
+        self.lanes > 1
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductionPlan {
+    pub target_items: Vec<String>,
+    /// Achievable rate per target, in the unit it was requested in
+    pub actual_rates: HashMap<String, Rate>,
+    pub required_facilities: Vec<FacilityRequirement>,
+    /// Raw material intake, per minute
+    pub raw_materials: HashMap<String, Rate>,
+    pub total_power: f64,
+    /// Cells covered by every required facility
+    #[serde(default)]
+    pub total_area: f64,
+    /// Every target, intermediate and raw material flow, by item id
+    #[serde(default)]
+    pub belt_flows: Vec<BeltFlow>,
+    /// Port-bound recipes, slowest first. Their extra machines are not part of `required_facilities`.
+    #[serde(default)]
+    pub port_bound: Vec<PortBound>,
+    pub constraint_limited: bool,
+    pub limiting_factor: Option<String>,
+}
+
+impl ProductionPlan {
+    pub fn parallel_belt_flows(&self) -> impl Iterator<Item = &BeltFlow> {
+        self.belt_flows.iter().filter(|f| f.needs_parallel_belts())
+    }
+}
+
+/// Scale factors at or above this are floating point noise, not a binding constraint
+const BINDING_SCALE: f64 = 0.999;
+
+pub struct RecipeSolver {
+    recipes: Vec<Recipe>,
+    facilities: HashMap<String, crate::facility::Facility>,
+}
+
+impl RecipeSolver {
+    pub fn new(
+        recipes: Vec<Recipe>,
+        facilities: HashMap<String, crate::facility::Facility>,
+    ) -> Self {
+        Self { recipes, facilities }
+    }
+
+    /// Solver over every recipe and facility in database.json
+    pub fn from_database() -> Result<Self, EngineError> {
+        let facilities = DataLoader::load_facilities()?.into_iter()
+            .map(|f| (f.id.clone(), f))
+            .collect();
+        let recipes = DataLoader::load_recipes()?.iter().map(Into::into).collect();
+        Ok(Self::new(recipes, facilities))
+    }
+
+    /// The solver's facilities in database.json's geometry shape, for the layout generator
+    fn geometry(&self) -> serde_json::Value {
+        serde_json::to_value(self.facilities.values().collect::<Vec<_>>()).unwrap_or_default()
+    }
+
+    /// Find all recipes that produce a given item
+    fn find_recipes_for_item(&self, item_id: &str) -> Vec<&Recipe> {
+        self.recipes
+            .iter()
+            .filter(|r| r.outputs.iter().any(|o| o.item_id == item_id))
+            .collect()
+    }
+
+    /// Production rate of one machine in items per minute; the solver works in per-minute
+    /// amounts internally and converts at the edges
+    fn calc_rate(amount: f64, crafting_time: f64) -> f64 {
+        // Validation for zero crafting time to prevent NaN
+        if crafting_time <= 0.0001 { return 0.0; }
+        (amount * 60.0) / crafting_time
+    }
+
+    /// `port_utilization` of one machine running `recipe` on `facility`, with each side's
+    /// ports split between the recipe's items by `busiest_port`
+    fn port_utilization(facility: &crate::facility::Facility, recipe: &Recipe, lane_per_minute: f64) -> f64 {
+        let ports = |kind: &str| facility.ports.as_ref()
+            .map_or(0, |ports| ports.iter().filter(|p| p.port_type == kind).count());
+        let crafts_per_minute = Self::calc_rate(1.0, recipe.crafting_time);
+        let load = |items_per_craft: Vec<f64>, ports: usize| {
+            Self::busiest_port(&items_per_craft, ports) * crafts_per_minute / lane_per_minute
+        };
+        let inputs = load(recipe.inputs.iter().map(|i| i.amount).collect(), ports("input"));
+        let outputs = load(recipe.outputs.iter().map(|o| o.amount).collect(), ports("output"));
+        inputs.max(outputs)
+    }
+
+    /// Load on the busiest of `ports` ports carrying items with these loads. While there are
+    /// enough ports each item gets its own, and each spare port goes to the item with the most
+    /// load per port. With fewer ports than items (thickeners, dismantlers) the items share
+    /// mixed belts evenly. 0 without ports or items.
+    fn busiest_port(loads: &[f64], ports: usize) -> f64 {
+        if ports == 0 || loads.is_empty() { return 0.0; }
+        if loads.len() > ports {
+            return loads.iter().sum::<f64>() / ports as f64;
+        }
+        let mut assigned = vec![1.0; loads.len()];
+        let per_port = |assigned: &[f64], i: usize| loads[i] / assigned[i];
+        for _ in loads.len()..ports {
+            if let Some(busiest) = (0..loads.len()).max_by(|&a, &b| per_port(&assigned, a).total_cmp(&per_port(&assigned, b))) {
+                assigned[busiest] += 1.0;
+            }
+        }
+        (0..loads.len()).map(|i| per_port(&assigned, i)).fold(0.0, f64::max)
+    }
+
+    /// Solve for an empty plate with the default layout constraints
+    pub fn solve(
+        &self,
+        target_items: Vec<(String, Rate)>, // (item_id, requested_rate)
+        plate_width: i32,
+        plate_height: i32,
+    ) -> Result<ProductionPlan, EngineError> {
+        self.solve_for_layout(target_items, &LayoutConstraints::for_plate(plate_width, plate_height))
+    }
+
+    /// Solve production requirements validating against "Hard Constraints" from Config.
+    /// Space is checked by packing the machines with the same generator (and terrain)
+    /// the layouts will come from.
+    #[tracing::instrument(level = "info", skip_all, err, fields(targets = target_items.len(), plate_width = constraints.plate_width, plate_height = constraints.plate_height))]
+    pub fn solve_for_layout(
+        &self,
+        target_items: Vec<(String, Rate)>, // (item_id, requested_rate)
+        constraints: &LayoutConstraints,
+    ) -> Result<ProductionPlan, EngineError> {
+        let (plate_width, plate_height) = (constraints.plate_width, constraints.plate_height);
+        if target_items.is_empty() {
+            return Err(EngineError::infeasible("No target items requested"));
+        }
+        if plate_width <= 0 || plate_height <= 0 {
+            return Err(EngineError::infeasible(format!("Plate size {}x{} is empty", plate_width, plate_height)));
+        }
+        if let Some((item_id, rate)) = target_items.iter().find(|(_, rate)| !(rate.amount.is_finite() && rate.amount > 0.0)) {
+            return Err(EngineError::InfeasiblePlan {
+                item_id: Some(item_id.clone()),
+                reason: format!("requested rate must be a positive number, got {}", rate),
+            });
+        }
+        let config = DataLoader::load_config()?;
+        
+        // Extract Simulation Constants
+        let base_power_budget = config["simulation_constants"]["base_power_budget"].as_f64().unwrap_or(5000.0);
+        let min_demand_threshold = config["optimization_constraints"]["min_demand_threshold"].as_f64().unwrap_or(0.0001);
+
+        // One belt lane carries the belt's throughput_limit; every port takes one lane
+        let belt_id = config["belt_id"].as_str().unwrap_or("item_port_log_belt_01");
+        let lane_capacity = self.facilities.get(belt_id)
+            .and_then(|belt| belt.throughput_limit)
+            .filter(|limit| *limit > 0.0)
+            .unwrap_or_else(|| LogisticsEdge::configured_throughput(&config));
+        let lane_capacity = Rate::per_second(lane_capacity as f64);
+        let lane_per_minute = lane_capacity.as_per_minute();
+
+        let mut required_facilities: Vec<FacilityRequirement> = Vec::new();
+        let mut item_demands: HashMap<String, f64> = HashMap::new();
+        let mut raw_materials: HashMap<String, f64> = HashMap::new();
+
+        // Initialize with target demands (items/min from here on)
+        for (item_id, rate) in &target_items {
+            item_demands.insert(item_id.clone(), rate.as_per_minute());
+        }
+
+        // 1. Calculate Theoretical Requirements (Top-Down Demand Propagation)
+        let mut stack: Vec<String> = target_items.iter().map(|(id, _)| id.clone()).collect();
+        let mut temp_processed = HashSet::new();
+
+        while let Some(item_id) = stack.pop() {
+            if temp_processed.contains(&item_id) { continue; }
+            
+            let demand = *item_demands.get(&item_id).unwrap_or(&0.0);
+            if demand <= min_demand_threshold { continue; }
+
+            let recipes = self.find_recipes_for_item(&item_id);
+            if recipes.is_empty() {
+                // Raw material node
+                raw_materials.insert(item_id.clone(), demand);
+                temp_processed.insert(item_id.clone());
+                continue;
+            }
+
+            // Simple heuristic: Take the first recipe (TODO: Advanced Recipe Selection)
+            let recipe = recipes[0];
+            
+            let output = recipe.outputs.iter().find(|o| o.item_id == item_id).unwrap();
+            let production_rate_per_facility = Self::calc_rate(output.amount, recipe.crafting_time);
+            if production_rate_per_facility <= 0.0 {
+                return Err(EngineError::InfeasiblePlan {
+                    item_id: Some(item_id),
+                    reason: format!("recipe {} has no crafting time or output", recipe.id),
+                });
+            }
+            
+            // demand is items/min. rate is items/min/facility.
+            let facility_count = demand / production_rate_per_facility;
+
+            // Get facility info
+            let facility = self.facilities.get(&recipe.facility_id)
+                .ok_or_else(|| EngineError::missing_facility(&recipe.facility_id))?;
+
+            required_facilities.push(FacilityRequirement {
+                facility_id: recipe.facility_id.clone(),
+                facility_type: facility.name.clone(),
+                count: facility_count,
+                recipe_id: recipe.id.clone(),
+                power: 0.0,
+                area: 0.0,
+                port_utilization: Self::port_utilization(facility, recipe, lane_per_minute),
+            });
+
+            // Add input demands to the map
+            for input in &recipe.inputs {
+                // Rate required = (input_amount / output_amount) * demand
+                let input_rate = (input.amount / output.amount) * demand;
+                *item_demands.entry(input.item_id.clone()).or_insert(0.0) += input_rate;
+                stack.push(input.item_id.clone());
+            }
+            
+            temp_processed.insert(item_id);
+        }
+
+        // 2. Allocate Raw Materials to Universal Providers
+        
+        let primary_provider_id = config["primary_provider_id"].as_str().unwrap_or("hub_pac_main").to_string();
+        let secondary_provider_id = config["secondary_provider_id"].as_str().unwrap_or("logistics_depot_unloader").to_string();
+
+        let total_raw_demand: f64 = raw_materials.values().sum();
+        
+        if total_raw_demand > 0.0 {
+            // Get PAC info for capacity check
+            let pac_meta = self.facilities.get(&primary_provider_id);
+            let pac_ports = pac_meta.map(|f| f.ports.as_ref().map(|p| p.iter().filter(|port| port.port_type == "output").count()).unwrap_or(0)).unwrap_or(6) as f64;
+            let pac_capacity = pac_ports * lane_per_minute;
+
+            // Always add 1 PAC if there's raw demand
+            if let Some(pac) = pac_meta {
+                required_facilities.push(FacilityRequirement {
+                    facility_id: primary_provider_id.clone(),
+                    facility_type: pac.name.clone(),
+                    count: 1.0,
+                    recipe_id: "universal_source_allocation".to_string(),
+                    power: 0.0,
+                    area: 0.0,
+                    port_utilization: 0.0,
+                });
+            }
+
+            // If PAC capacity is exceeded, add Unloaders
+            if total_raw_demand > pac_capacity {
+                let overflow = total_raw_demand - pac_capacity;
+                let unloader_meta = self.facilities.get(&secondary_provider_id);
+                let unloader_ports = unloader_meta.map(|f| f.ports.as_ref().map(|p| p.iter().filter(|port| port.port_type == "output").count()).unwrap_or(1)).unwrap_or(1) as f64;
+                let unloader_count = (overflow / (unloader_ports * lane_per_minute)).ceil();
+
+                if let Some(unloader) = unloader_meta {
+                    required_facilities.push(FacilityRequirement {
+                        facility_id: secondary_provider_id.clone(),
+                        facility_type: unloader.name.clone(),
+                        count: unloader_count,
+                        recipe_id: "universal_source_allocation".to_string(),
+                        power: 0.0,
+                        area: 0.0,
+                        port_utilization: 0.0,
+                    });
+                }
+            }
+        }
+
+        // 3. Validate Constraints (The "Bottleneck" Check)
+        let mut total_facility_area = 0.0;
+        let mut total_power_consumption = 0.0;
+
+        for req in &mut required_facilities {
+            if let Some(fac) = self.facilities.get(&req.facility_id) {
+                // Area = width * height * count
+                req.area = (fac.width * fac.height) as f64 * req.count;
+                total_facility_area += req.area;
+                
+                // Power = power * count
+                req.power = fac.power_consumption as f64 * req.count;
+                total_power_consumption += req.power;
+            }
+        }
+
+        // 3. Calculate Scale Factor (Weakest Link Principle). Space and power cap how many
+        // machines get built, belts how fast they run; output is cut by both.
+        let mut machine_scale = 1.0;
+        let mut machine_limit = None;
+
+        // Space Constraint: how much of the plan the layout generator can actually place
+        let required: Vec<(String, String, f64)> = required_facilities.iter()
+            .map(|req| (req.facility_id.clone(), req.facility_type.clone(), req.count))
+            .collect();
+        let area_scale = LayoutGenerator::new(constraints.clone(), self.geometry()).packing_scale(&required);
+        if area_scale <= 0.0 {
+            return Err(EngineError::infeasible(format!(
+                "Plate {}x{} has no room for the power source and one of each machine", plate_width, plate_height
+            )));
+        }
+        if area_scale < BINDING_SCALE {
+            machine_scale = area_scale;
+            machine_limit = Some(format!("Space Constraints (Capacity at {:.0}%)", area_scale * 100.0));
+        }
+        
+        // Power Constraint
+        if total_power_consumption > base_power_budget {
+            let power_scale = base_power_budget / total_power_consumption;
+            if power_scale < machine_scale.min(BINDING_SCALE) {
+                machine_scale = power_scale;
+                machine_limit = Some(format!("Power Grid Overload (Capacity at {:.0}%)", power_scale * 100.0));
+            }
+        }
+
+        // Belt Constraint: each port carries one lane, so a port-bound machine runs at
+        // 1 / port_utilization of its speed and the chain at the pace of its slowest recipe
+        let is_port_bound = |req: &&FacilityRequirement| 1.0 / req.port_utilization < BINDING_SCALE;
+        let slowest = required_facilities.iter().filter(is_port_bound).max_by(|a, b| a.port_utilization.total_cmp(&b.port_utilization));
+        let belt_scale = slowest.map_or(1.0, |req| 1.0 / req.port_utilization);
+        let belt_limit = slowest.map(|req| {
+            format!("Belt Throughput at {} (Capacity at {:.0}%)", req.recipe_id, belt_scale * 100.0)
+        });
+
+        let scale_factor = machine_scale * belt_scale;
+        let limits: Vec<String> = machine_limit.into_iter().chain(belt_limit).collect();
+        let limiting_factor = (!limits.is_empty()).then(|| limits.join("; "));
+
+        // 4. Apply Scaling and Finalize Plan
+        if machine_scale < 1.0 {
+            for req in &mut required_facilities {
+                req.count *= machine_scale;
+                req.power *= machine_scale;
+                req.area *= machine_scale;
+            }
+            total_power_consumption *= machine_scale;
+            total_facility_area *= machine_scale;
+        }
+        let mut port_bound: Vec<PortBound> = required_facilities.iter()
+            .filter(is_port_bound)
+            .map(|req| PortBound {
+                recipe_id: req.recipe_id.clone(),
+                port_utilization: req.port_utilization,
+                extra_machines: req.count * (req.port_utilization - 1.0),
+            })
+            .collect();
+        port_bound.sort_by(|a, b| b.port_utilization.total_cmp(&a.port_utilization));
+        for val in raw_materials.values_mut() {
+            *val *= scale_factor;
+        }
+
+        let mut actual_rates = HashMap::new();
+        for (item_id, target_rate) in target_items {
+            actual_rates.insert(item_id, target_rate.scaled(scale_factor));
+        }
+
+        let mut belt_flows: Vec<BeltFlow> = item_demands.iter()
+            .filter(|(_, demand)| **demand > min_demand_threshold)
+            .map(|(item_id, demand)| {
+                let per_minute = demand * scale_factor;
+                BeltFlow {
+                    item_id: item_id.clone(),
+                    rate: Rate::per_minute(per_minute),
+                    // Tolerance so a flow of exactly one lane doesn't round up to two
+                    lanes: (per_minute / lane_per_minute - 1e-9).ceil().max(1.0) as u32,
+                }
+            })
+            .collect();
+        belt_flows.sort_by(|a, b| a.item_id.cmp(&b.item_id));
+
+        tracing::debug!(
+            facility_types = required_facilities.len(),
+            total_power = total_power_consumption,
+            total_area = total_facility_area,
+            limiting_factor = limiting_factor.as_deref(),
+            parallel_belt_flows = belt_flows.iter().filter(|f| f.needs_parallel_belts()).count(),
+            "plan solved"
+        );
+        Ok(ProductionPlan {
+            target_items: actual_rates.keys().cloned().collect(),
+            actual_rates,
+            required_facilities,
+            raw_materials: raw_materials.into_iter().map(|(id, rate)| (id, Rate::per_minute(rate))).collect(),
+            total_power: total_power_consumption,
+            total_area: total_facility_area,
+            belt_flows,
+            port_bound,
+            constraint_limited: limiting_factor.is_some(),
+            limiting_factor,
+        })
+    }
+}
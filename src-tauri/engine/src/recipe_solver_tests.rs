@@ -0,0 +1,226 @@
+use crate::recipe_solver::{Recipe, RecipeInput, RecipeOutput, RecipeSolver};
+use crate::facility::{Facility, Port};
+use crate::layout_generator::{LayoutConstraints, LayoutGenerator};
+use crate::rate::Rate;
+use std::collections::HashMap;
+
+#[test]
+fn test_solve_unconstrained() {
+    let recipes = vec![
+        Recipe {
+            id: "recipe_iron".to_string(),
+            name: Some("Iron Recipe".to_string()),
+            inputs: vec![],
+            outputs: vec![RecipeOutput { item_id: "iron".to_string(), amount: 1.0 }],
+            facility_id: "smelter".to_string(),
+            crafting_time: 2.0, // 30 per minute
+        }
+    ];
+    let mut facilities = HashMap::new();
+    facilities.insert("smelter".to_string(), Facility {
+        id: "smelter".to_string(),
+        name: "Smelter".to_string(),
+        width: 3,
+        height: 3,
+        power_consumption: 10.0f32,
+        tier: 1,
+        icon: None,
+        category: None,
+        ports: None,
+        input_slots: None,
+        output_slots: None,
+        throughput_limit: None,
+        is_filter: None,
+    });
+
+    let solver = RecipeSolver::new(recipes, facilities);
+    
+    // Request 300/min. Theoretical: 10 smelters (30/min each)
+    let target = vec![("iron".to_string(), Rate::per_minute(300.0))];
+    
+    // Plate 100x100 (Huge)
+    let plan = solver.solve(target, 100, 100).unwrap();
+    
+    assert!(!plan.constraint_limited);
+    assert_eq!(plan.required_facilities.len(), 1);
+    assert_eq!(plan.required_facilities[0].count, 10.0);
+
+    // Default lanes carry 30 per minute, so 300 iron per minute needs 10 parallel belts
+    let iron: Vec<_> = plan.parallel_belt_flows().map(|f| (f.item_id.as_str(), f.lanes)).collect();
+    assert_eq!(iron, vec![("iron", 10)]);
+    assert_eq!(plan.total_power, 100.0);
+}
+
+#[test]
+fn test_solve_space_constrained() {
+    let recipes = vec![
+        Recipe {
+            id: "recipe_iron".to_string(),
+            name: Some("Iron Recipe".to_string()),
+            inputs: vec![],
+            outputs: vec![RecipeOutput { item_id: "iron".to_string(), amount: 1.0 }],
+            facility_id: "smelter".to_string(),
+            crafting_time: 2.0, // 30 per minute
+        }
+    ];
+    let mut facilities = HashMap::new();
+    facilities.insert("smelter".to_string(), Facility {
+        id: "smelter".to_string(),
+        name: "Smelter".to_string(),
+        width: 3,
+        height: 3, // Area 9
+        power_consumption: 10.0f32,
+        tier: 1,
+        icon: None,
+        category: None,
+        ports: None,
+        input_slots: None,
+        output_slots: None,
+        throughput_limit: None,
+        is_filter: None,
+    });
+
+    let solver = RecipeSolver::new(recipes, facilities);
+    
+    // Request 300/min -> Needs 10 smelters, each 3x3
+    let target = vec![("iron".to_string(), Rate::per_minute(300.0))];
+    
+    // Plate 13x13: the 8x9 power source sits in the corner and the spiral packs
+    // smelters around it until the plate runs out
+    let plan = solver.solve(target.clone(), 13, 13).unwrap();
+    
+    assert!(plan.constraint_limited);
+    assert!(plan.limiting_factor.as_deref().unwrap().contains("Space"));
+    
+    // The plan scales to exactly what the layout generator fits
+    let smelters = plan.required_facilities[0].count;
+    let actual_rate = plan.actual_rates["iron"].as_per_minute();
+    assert!(smelters < 10.0 && smelters > 1.0);
+    assert!((actual_rate - smelters * 30.0).abs() < 1e-9);
+
+    let geometry = serde_json::json!([{ "id": "smelter", "name": "Smelter", "width": 3, "height": 3 }]);
+    let generator = LayoutGenerator::new(LayoutConstraints::for_plate(13, 13), geometry);
+    let required = |count: f64| vec![("smelter".to_string(), "Smelter".to_string(), count)];
+    assert_eq!(generator.packing_scale(&required(smelters)), 1.0);
+    assert!(generator.packing_scale(&required(smelters.ceil() + 1.0)) < 1.0);
+
+    // Obstacles take room the old fixed ratio never saw
+    let mut constraints = LayoutConstraints::for_plate(13, 13);
+    constraints.terrain.blocked = (0..13).flat_map(|x| (10..13).map(move |y| (x, y))).collect();
+    let blocked = solver.solve_for_layout(target, &constraints).unwrap();
+    assert!(blocked.actual_rates["iron"].as_per_minute() < actual_rate);
+
+    // Not even the power source fits
+    assert_eq!(solver.solve(vec![("iron".to_string(), Rate::per_minute(30.0))], 10, 10).unwrap_err().code(), "infeasible_plan");
+}
+
+fn port(id: &str, port_type: &str) -> Port {
+    Port { id: id.to_string(), x: 0, y: 0, port_type: port_type.to_string(), direction: "left".to_string() }
+}
+
+#[test]
+fn test_solve_belt_constrained() {
+    let recipes = vec![
+        Recipe {
+            id: "recipe_powder".to_string(),
+            name: None,
+            inputs: vec![RecipeInput { item_id: "ore".to_string(), amount: 4.0 }],
+            outputs: vec![RecipeOutput { item_id: "powder".to_string(), amount: 1.0 }],
+            facility_id: "grinder".to_string(),
+            crafting_time: 2.0, // Takes 120 ore per minute through one input port
+        }
+    ];
+    let facility = |id: &str, ports: Vec<Port>, throughput_limit: Option<f32>| Facility {
+        id: id.to_string(),
+        name: id.to_string(),
+        width: 3,
+        height: 3,
+        power_consumption: 10.0f32,
+        tier: 1,
+        icon: None,
+        category: None,
+        ports: Some(ports),
+        input_slots: None,
+        output_slots: None,
+        throughput_limit,
+        is_filter: None,
+    };
+    let mut facilities = HashMap::new();
+    facilities.insert("grinder".to_string(), facility("grinder", vec![port("in_1", "input"), port("out_1", "output")], None));
+    // 1 item/s = 60 per minute per lane
+    facilities.insert("item_port_log_belt_01".to_string(), facility("item_port_log_belt_01", Vec::new(), Some(1.0)));
+
+    let solver = RecipeSolver::new(recipes, facilities);
+    let plan = solver.solve(vec![("powder".to_string(), Rate::per_minute(30.0))], 100, 100).unwrap();
+
+    // The grinder's one input lane carries half of what it needs, so it runs at half speed.
+    // The machine count is what the plate has to fit and power; the second grinder that
+    // would restore full output is listed apart.
+    let grinders = plan.required_facilities.iter().find(|r| r.recipe_id == "recipe_powder").unwrap();
+    assert_eq!(grinders.port_utilization, 2.0);
+    assert_eq!((grinders.count, grinders.power, grinders.area), (1.0, 10.0, 9.0));
+    assert_eq!(plan.port_bound.len(), 1);
+    assert_eq!((plan.port_bound[0].recipe_id.as_str(), plan.port_bound[0].extra_machines), ("recipe_powder", 1.0));
+
+    assert!(plan.constraint_limited);
+    assert_eq!(plan.limiting_factor.as_deref(), Some("Belt Throughput at recipe_powder (Capacity at 50%)"));
+    assert_eq!(plan.actual_rates["powder"], Rate::per_minute(15.0));
+
+    // The one lane into the grinder is all the ore that moves
+    let ore = plan.belt_flows.iter().find(|f| f.item_id == "ore").unwrap();
+    assert_eq!((ore.rate, ore.lanes), (Rate::per_minute(60.0), 1));
+    assert_eq!(plan.parallel_belt_flows().count(), 0);
+
+    // Three of the four grinders fit next to the PAC, each at half speed: 45/min
+    let plan = solver.solve(vec![("powder".to_string(), Rate::per_minute(120.0))], 12, 12).unwrap();
+    let grinders = plan.required_facilities.iter().find(|r| r.recipe_id == "recipe_powder").unwrap();
+    assert_eq!((grinders.count, grinders.power), (3.0, 30.0));
+    assert_eq!(plan.port_bound[0].extra_machines, 3.0);
+    assert_eq!(plan.actual_rates["powder"], Rate::per_minute(45.0));
+    assert_eq!(
+        plan.limiting_factor.as_deref(),
+        Some("Space Constraints (Capacity at 75%); Belt Throughput at recipe_powder (Capacity at 50%)"),
+    );
+}
+
+#[test]
+fn test_ports_split_between_items() {
+    let recipe = |inputs: Vec<(&str, f64)>| Recipe {
+        id: "recipe_steel".to_string(),
+        name: None,
+        inputs: inputs.into_iter().map(|(item_id, amount)| RecipeInput { item_id: item_id.to_string(), amount }).collect(),
+        outputs: vec![RecipeOutput { item_id: "steel".to_string(), amount: 1.0 }],
+        facility_id: "forge".to_string(),
+        crafting_time: 2.0,
+    };
+    let facilities = || HashMap::from([
+        ("forge".to_string(), Facility {
+            id: "forge".to_string(),
+            name: "Forge".to_string(),
+            width: 3,
+            height: 3,
+            power_consumption: 10.0f32,
+            tier: 1,
+            icon: None,
+            category: None,
+            ports: Some(vec![port("in_1", "input"), port("in_2", "input"), port("in_3", "input"), port("out_1", "output")]),
+            input_slots: None,
+            output_slots: None,
+            throughput_limit: None,
+            is_filter: None,
+        }),
+    ]);
+    let target = || vec![("steel".to_string(), Rate::per_minute(30.0))];
+
+    // 90 ore and 30 coal per minute over three default 30/min ports: coal needs a port of
+    // its own, so ore gets two at 45/min each instead of sharing all three
+    let solver = RecipeSolver::new(vec![recipe(vec![("ore", 3.0), ("coal", 1.0)])], facilities());
+    let plan = solver.solve(target(), 100, 100).unwrap();
+    assert_eq!(plan.required_facilities[0].port_utilization, 1.5);
+    assert_eq!(plan.actual_rates["steel"], Rate::per_minute(20.0));
+
+    // Four ingredients can't get a port each; they share the three as mixed belts
+    let solver = RecipeSolver::new(vec![recipe(vec![("ore", 1.0), ("coal", 1.0), ("sand", 1.0), ("flux", 2.0)])], facilities());
+    let plan = solver.solve(target(), 100, 100).unwrap();
+    assert!((plan.required_facilities[0].port_utilization - 5.0 / 3.0).abs() < 1e-9);
+}
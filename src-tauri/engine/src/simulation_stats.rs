@@ -0,0 +1,433 @@
+use crate::facility::PlacedFacility;
+use crate::logistics::LogisticsEdge;
+use crate::recipe::Recipe;
+use crate::rate::Rate;
+use crate::recipe_solver::ProductionPlan;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Length of one statistics bucket in simulated seconds
+const BUCKET_SECONDS: f64 = 1.0;
+/// Default span of the rolling window in simulated seconds
+const DEFAULT_WINDOW_SECONDS: f64 = 60.0;
+/// How many bottlenecks the report names
+const MAX_BOTTLENECKS: usize = 5;
+
+/// What a production facility spent a simulation step doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FacilityStatus {
+    Crafting,
+    /// Nothing in the input buffer and nothing feeding it
+    Idle,
+    /// Fed (or partially filled) but no recipe has all of its inputs
+    Starved,
+    /// Inputs are ready but the output buffer cannot take the result
+    OutputBlocked,
+    Unpowered,
+}
+
+impl FacilityStatus {
+    const COUNT: usize = 5;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct FacilitySample {
+    facility_id: String,
+    status_seconds: [f64; FacilityStatus::COUNT],
+    crafts: u32,
+    produced: HashMap<String, f64>,
+    last_recipe_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct StatsBucket {
+    start: f64,
+    duration: f64,
+    facilities: HashMap<String, FacilitySample>,
+    edges: HashMap<String, u32>,
+}
+
+/// Rolling per-facility and per-edge counters collected by `LogisticsEngine::step`
+#[derive(Debug, Clone)]
+pub struct SimulationStats {
+    window_seconds: f64,
+    buckets: VecDeque<StatsBucket>,
+}
+
+impl Default for SimulationStats {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW_SECONDS)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacilityStats {
+    pub instance_id: String,
+    pub facility_id: String,
+    pub recipe_id: Option<String>,
+    pub crafts_per_minute: f64,
+    /// Ideal rate for the recipe it ran (60 / crafting time)
+    pub expected_crafts_per_minute: Option<f64>,
+    pub crafting_pct: f64,
+    pub idle_pct: f64,
+    pub starved_pct: f64,
+    pub output_blocked_pct: f64,
+    pub unpowered_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeStats {
+    pub from_instance_id: String,
+    pub from_port_id: String,
+    pub to_instance_id: String,
+    pub to_port_id: String,
+    pub items_moved: u32,
+    pub items_per_minute: f64,
+    /// Measured flow divided by the edge throughput
+    pub utilization: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bottleneck {
+    pub instance_id: String,
+    pub facility_id: String,
+    pub reason: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateComparison {
+    pub item_id: String,
+    /// Both in the unit the plan's target was requested in
+    pub predicted: Rate,
+    pub measured: Rate,
+    /// measured / predicted, None when nothing was predicted
+    pub ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub window_seconds: f64,
+    pub facilities: Vec<FacilityStats>,
+    pub edges: Vec<EdgeStats>,
+    pub bottlenecks: Vec<Bottleneck>,
+    pub rate_comparison: Vec<RateComparison>,
+}
+
+impl SimulationStats {
+    pub fn new(window_seconds: f64) -> Self {
+        Self {
+            window_seconds: window_seconds.max(BUCKET_SECONDS),
+            buckets: VecDeque::new(),
+        }
+    }
+
+    /// Opens (or extends) the bucket for this step and drops buckets that left the window
+    pub fn begin_step(&mut self, sim_time: f64, dt: f64) {
+        let bucket_start = (sim_time / BUCKET_SECONDS).floor() * BUCKET_SECONDS;
+
+        // Clock went backwards (grid reset) -> old samples are meaningless
+        if self.buckets.back().is_some_and(|b| b.start > bucket_start) {
+            self.buckets.clear();
+        }
+        if self.buckets.back().is_none_or(|b| b.start < bucket_start) {
+            self.buckets.push_back(StatsBucket {
+                start: bucket_start,
+                ..Default::default()
+            });
+        }
+        if let Some(bucket) = self.buckets.back_mut() {
+            bucket.duration += dt;
+        }
+
+        let cutoff = sim_time - self.window_seconds;
+        while self.buckets.front().is_some_and(|b| b.start + BUCKET_SECONDS <= cutoff) {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn sample_mut(&mut self, instance_id: &str, facility_id: &str) -> Option<&mut FacilitySample> {
+        let bucket = self.buckets.back_mut()?;
+        let sample = bucket.facilities.entry(instance_id.to_string()).or_default();
+        if sample.facility_id.is_empty() {
+            sample.facility_id = facility_id.to_string();
+        }
+        Some(sample)
+    }
+
+    pub fn record_status(&mut self, facility: &PlacedFacility, status: FacilityStatus, dt: f64) {
+        if let Some(sample) = self.sample_mut(&facility.instance_id, &facility.facility_id) {
+            sample.status_seconds[status.index()] += dt;
+        }
+    }
+
+    pub fn record_craft(&mut self, facility: &PlacedFacility, recipe: &Recipe) {
+        if let Some(sample) = self.sample_mut(&facility.instance_id, &facility.facility_id) {
+            sample.crafts += 1;
+            sample.last_recipe_id = Some(recipe.id.clone());
+            for output in &recipe.outputs {
+                *sample.produced.entry(output.item_id.clone()).or_insert(0.0) += output.amount as f64;
+            }
+        }
+    }
+
+    /// Counts items a miner pulled out of a resource node as produced
+    pub fn record_extraction(&mut self, facility: &PlacedFacility, item_id: &str, count: u32) {
+        if let Some(sample) = self.sample_mut(&facility.instance_id, &facility.facility_id) {
+            *sample.produced.entry(item_id.to_string()).or_insert(0.0) += count as f64;
+        }
+    }
+
+    pub fn record_transfer(&mut self, edge_key: &str, count: u32) {
+        if let Some(bucket) = self.buckets.back_mut() {
+            *bucket.edges.entry(edge_key.to_string()).or_insert(0) += count;
+        }
+    }
+
+    /// Simulated seconds currently covered by the window
+    pub fn observed_seconds(&self) -> f64 {
+        self.buckets.iter().map(|b| b.duration).sum()
+    }
+
+    /// Summed samples per facility over the whole window
+    fn aggregate_facilities(&self) -> HashMap<String, FacilitySample> {
+        let mut totals: HashMap<String, FacilitySample> = HashMap::new();
+        for bucket in &self.buckets {
+            for (instance_id, sample) in &bucket.facilities {
+                let total = totals.entry(instance_id.clone()).or_default();
+                total.facility_id = sample.facility_id.clone();
+                for (i, secs) in sample.status_seconds.iter().enumerate() {
+                    total.status_seconds[i] += secs;
+                }
+                total.crafts += sample.crafts;
+                for (item_id, amount) in &sample.produced {
+                    *total.produced.entry(item_id.clone()).or_insert(0.0) += amount;
+                }
+                if sample.last_recipe_id.is_some() {
+                    total.last_recipe_id = sample.last_recipe_id.clone();
+                }
+            }
+        }
+        totals
+    }
+
+    fn aggregate_edges(&self) -> HashMap<String, u32> {
+        let mut totals = HashMap::new();
+        for bucket in &self.buckets {
+            for (key, count) in &bucket.edges {
+                *totals.entry(key.clone()).or_insert(0) += count;
+            }
+        }
+        totals
+    }
+
+    /// Items produced per minute over the window, by item id
+    pub fn produced_per_minute(&self) -> HashMap<String, f64> {
+        let minutes = self.observed_seconds() / 60.0;
+        let mut rates: HashMap<String, f64> = HashMap::new();
+        if minutes <= 0.0 { return rates; }
+        for sample in self.aggregate_facilities().values() {
+            for (item_id, amount) in &sample.produced {
+                *rates.entry(item_id.clone()).or_insert(0.0) += amount / minutes;
+            }
+        }
+        rates
+    }
+
+    /// Items moved per minute over the window, by edge key
+    pub fn transfers_per_minute(&self) -> HashMap<String, f64> {
+        let minutes = self.observed_seconds() / 60.0;
+        if minutes <= 0.0 { return HashMap::new(); }
+        self.aggregate_edges().into_iter()
+            .map(|(key, count)| (key, count as f64 / minutes))
+            .collect()
+    }
+
+    /// Builds the report for the facilities and edges currently on the grid.
+    /// `plan` is the last solver result, used to compare measured against predicted rates.
+    pub fn report(
+        &self,
+        facilities: &[PlacedFacility],
+        edges: &[LogisticsEdge],
+        recipes: &[Recipe],
+        plan: Option<&ProductionPlan>,
+    ) -> SimulationReport {
+        let window_seconds = self.observed_seconds();
+        let minutes = window_seconds / 60.0;
+        let per_minute = |count: f64| if minutes > 0.0 { count / minutes } else { 0.0 };
+        let pct = |secs: f64| if window_seconds > 0.0 { secs / window_seconds * 100.0 } else { 0.0 };
+
+        let samples = self.aggregate_facilities();
+        let edge_counts = self.aggregate_edges();
+
+        let mut facility_stats = Vec::new();
+        for facility in facilities {
+            let Some(sample) = samples.get(&facility.instance_id) else { continue };
+            let recipe_id = sample.last_recipe_id.clone().or_else(|| facility.active_recipe_id.clone());
+            let expected = recipe_id
+                .as_ref()
+                .and_then(|id| recipes.iter().find(|r| &r.id == id))
+                .filter(|r| r.crafting_time > 0.0)
+                .map(|r| 60.0 / r.crafting_time as f64);
+
+            facility_stats.push(FacilityStats {
+                instance_id: facility.instance_id.clone(),
+                facility_id: facility.facility_id.clone(),
+                recipe_id,
+                crafts_per_minute: per_minute(sample.crafts as f64),
+                expected_crafts_per_minute: expected,
+                crafting_pct: pct(sample.status_seconds[FacilityStatus::Crafting.index()]),
+                idle_pct: pct(sample.status_seconds[FacilityStatus::Idle.index()]),
+                starved_pct: pct(sample.status_seconds[FacilityStatus::Starved.index()]),
+                output_blocked_pct: pct(sample.status_seconds[FacilityStatus::OutputBlocked.index()]),
+                unpowered_pct: pct(sample.status_seconds[FacilityStatus::Unpowered.index()]),
+            });
+        }
+
+        let edge_stats: Vec<EdgeStats> = edges
+            .iter()
+            .map(|edge| {
+                let moved = edge_counts.get(&edge.key()).copied().unwrap_or(0);
+                let rate = per_minute(moved as f64);
+                let capacity = Rate::per_second(edge.throughput as f64).as_per_minute();
+                EdgeStats {
+                    from_instance_id: edge.from_instance_id.clone(),
+                    from_port_id: edge.from_port_id.clone(),
+                    to_instance_id: edge.to_instance_id.clone(),
+                    to_port_id: edge.to_port_id.clone(),
+                    items_moved: moved,
+                    items_per_minute: rate,
+                    utilization: if capacity > 0.0 { rate / capacity } else { 0.0 },
+                }
+            })
+            .collect();
+
+        let bottlenecks = Self::find_bottlenecks(&facility_stats, edges);
+
+        let mut rate_comparison = Vec::new();
+        if let Some(plan) = plan {
+            for (item_id, predicted) in &plan.actual_rates {
+                let produced: f64 = samples
+                    .values()
+                    .filter_map(|s| s.produced.get(item_id))
+                    .sum();
+                let measured = Rate::per_minute(per_minute(produced)).to_unit(predicted.unit);
+                rate_comparison.push(RateComparison {
+                    item_id: item_id.clone(),
+                    predicted: *predicted,
+                    measured,
+                    ratio: predicted.ratio_of(measured),
+                });
+            }
+            rate_comparison.sort_by(|a, b| a.item_id.cmp(&b.item_id));
+        }
+
+        SimulationReport {
+            window_seconds,
+            facilities: facility_stats,
+            edges: edge_stats,
+            bottlenecks,
+            rate_comparison,
+        }
+    }
+
+    /// Ranks facilities that hold the line back:
+    /// - saturated machines whose producers are blocked or whose consumers starve
+    /// - machines that spend time without power
+    /// - line ends whose output is never drained
+    fn find_bottlenecks(stats: &[FacilityStats], edges: &[LogisticsEdge]) -> Vec<Bottleneck> {
+        let by_id: HashMap<&str, &FacilityStats> =
+            stats.iter().map(|s| (s.instance_id.as_str(), s)).collect();
+
+        let mut bottlenecks = Vec::new();
+        for s in stats {
+            let upstream = Self::tracked_neighbours(&s.instance_id, edges, &by_id, true);
+            let downstream = Self::tracked_neighbours(&s.instance_id, edges, &by_id, false);
+
+            let average = |ids: &[&str], f: fn(&FacilityStats) -> f64| {
+                if ids.is_empty() {
+                    0.0
+                } else {
+                    ids.iter().map(|id| f(by_id[id])).sum::<f64>() / ids.len() as f64
+                }
+            };
+            let upstream_blocked = average(&upstream, |n| n.output_blocked_pct);
+            let downstream_starved = average(&downstream, |n| n.starved_pct);
+
+            let saturation = s.crafting_pct / 100.0 * upstream_blocked.max(downstream_starved);
+            if saturation > 0.0 {
+                bottlenecks.push(Bottleneck {
+                    instance_id: s.instance_id.clone(),
+                    facility_id: s.facility_id.clone(),
+                    reason: format!(
+                        "Running {:.0}% of the time while neighbours wait (upstream blocked {:.0}%, downstream starved {:.0}%)",
+                        s.crafting_pct, upstream_blocked, downstream_starved
+                    ),
+                    score: saturation,
+                });
+            }
+
+            if s.unpowered_pct > 0.0 {
+                bottlenecks.push(Bottleneck {
+                    instance_id: s.instance_id.clone(),
+                    facility_id: s.facility_id.clone(),
+                    reason: format!("Unpowered {:.0}% of the time", s.unpowered_pct),
+                    score: s.unpowered_pct,
+                });
+            }
+
+            let drained = edges.iter().any(|e| e.from_instance_id == s.instance_id);
+            if !drained && s.output_blocked_pct > 0.0 {
+                bottlenecks.push(Bottleneck {
+                    instance_id: s.instance_id.clone(),
+                    facility_id: s.facility_id.clone(),
+                    reason: format!("Output never collected, blocked {:.0}% of the time", s.output_blocked_pct),
+                    score: s.output_blocked_pct,
+                });
+            }
+        }
+
+        bottlenecks.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        bottlenecks.truncate(MAX_BOTTLENECKS);
+        bottlenecks
+    }
+
+    /// Nearest tracked (recipe-running) facilities along edges, skipping belts and other pass-through nodes
+    fn tracked_neighbours<'a>(
+        start: &str,
+        edges: &'a [LogisticsEdge],
+        tracked: &HashMap<&str, &FacilityStats>,
+        upstream: bool,
+    ) -> Vec<&'a str> {
+        let mut found = Vec::new();
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut frontier: Vec<&str> = vec![start];
+
+        while let Some(current) = frontier.pop() {
+            for edge in edges {
+                let (from, to) = (edge.from_instance_id.as_str(), edge.to_instance_id.as_str());
+                let next = if upstream && to == current {
+                    from
+                } else if !upstream && from == current {
+                    to
+                } else {
+                    continue;
+                };
+                if next == start || !visited.insert(next) {
+                    continue;
+                }
+                if tracked.contains_key(next) {
+                    found.push(next);
+                } else {
+                    frontier.push(next);
+                }
+            }
+        }
+        found
+    }
+}
@@ -0,0 +1,134 @@
+use crate::facility::PlacedFacility;
+use crate::logistics::LogisticsEdge;
+use crate::rate::Rate;
+use crate::recipe::{Recipe, RecipeIngredient};
+use crate::recipe_solver::ProductionPlan;
+use crate::simulation_stats::{FacilityStatus, SimulationStats};
+use std::collections::HashMap;
+
+const DT: f64 = 0.5;
+
+/// Two seconds per nugget
+fn smelt() -> Recipe {
+    Recipe {
+        id: "smelt".to_string(),
+        name: None,
+        inputs: vec![RecipeIngredient { item_id: "item_iron_ore".to_string(), amount: 1.0 }],
+        outputs: vec![RecipeIngredient { item_id: "item_iron_nugget".to_string(), amount: 1.0 }],
+        crafting_time: 2.0,
+        facility_id: "item_port_furnance_1".to_string(),
+    }
+}
+
+/// `smelter` crafts non-stop into `packer`, which starves; `slow` crafts half the time
+/// into `sink`, which also starves. One nugget crosses each belt per craft.
+fn sixty_seconds() -> (SimulationStats, Vec<PlacedFacility>, Vec<LogisticsEdge>) {
+    let facilities = vec![
+        PlacedFacility::new("smelter", "item_port_furnance_1", 0, 0),
+        PlacedFacility::new("packer", "item_port_furnance_1", 4, 0),
+        PlacedFacility::new("slow", "item_port_furnance_1", 0, 4),
+        PlacedFacility::new("sink", "item_port_furnance_1", 4, 4),
+    ];
+    // 1 item/s belts
+    let belt = |from: &str, to: &str| LogisticsEdge { throughput: 1.0, ..LogisticsEdge::new(from, "out_1", to, "in_1", "item_iron_nugget") };
+    let edges = vec![belt("smelter", "packer"), belt("slow", "sink")];
+    let recipe = smelt();
+
+    let mut stats = SimulationStats::new(60.0);
+    for step in 0..120 {
+        stats.begin_step(step as f64 * DT, DT);
+        stats.record_status(&facilities[0], FacilityStatus::Crafting, DT);
+        stats.record_status(&facilities[1], FacilityStatus::Starved, DT);
+        let slow_status = if step % 2 == 0 { FacilityStatus::Crafting } else { FacilityStatus::Idle };
+        stats.record_status(&facilities[2], slow_status, DT);
+        stats.record_status(&facilities[3], FacilityStatus::Starved, DT);
+
+        if step % 4 == 3 {
+            stats.record_craft(&facilities[0], &recipe);
+            stats.record_transfer(&edges[0].key(), 1);
+        }
+        if step % 8 == 7 {
+            stats.record_craft(&facilities[2], &recipe);
+            stats.record_transfer(&edges[1].key(), 1);
+        }
+    }
+    (stats, facilities, edges)
+}
+
+#[test]
+fn test_crafts_per_minute() {
+    let (stats, facilities, edges) = sixty_seconds();
+    let report = stats.report(&facilities, &edges, &[smelt()], None);
+
+    assert_eq!(report.window_seconds, 60.0);
+    let smelter = report.facilities.iter().find(|f| f.instance_id == "smelter").unwrap();
+    assert_eq!(smelter.recipe_id.as_deref(), Some("smelt"));
+    assert_eq!(smelter.crafts_per_minute, 30.0);
+    assert_eq!(smelter.expected_crafts_per_minute, Some(30.0));
+    assert_eq!(smelter.crafting_pct, 100.0);
+
+    let slow = report.facilities.iter().find(|f| f.instance_id == "slow").unwrap();
+    assert_eq!(slow.crafts_per_minute, 15.0);
+    assert_eq!(slow.crafting_pct, 50.0);
+    assert_eq!(slow.idle_pct, 50.0);
+}
+
+#[test]
+fn test_items_moved_per_edge() {
+    let (stats, facilities, edges) = sixty_seconds();
+    let report = stats.report(&facilities, &edges, &[smelt()], None);
+
+    let fast = &report.edges[0];
+    assert_eq!((fast.from_instance_id.as_str(), fast.to_instance_id.as_str()), ("smelter", "packer"));
+    assert_eq!(fast.items_moved, 30);
+    assert_eq!(fast.items_per_minute, 30.0);
+    // 1 item/s belt
+    assert_eq!(fast.utilization, 0.5);
+
+    assert_eq!(report.edges[1].items_moved, 15);
+    assert_eq!(stats.transfers_per_minute()[&edges[1].key()], 15.0);
+}
+
+#[test]
+fn test_bottlenecks_ranked_by_saturation() {
+    let (stats, facilities, edges) = sixty_seconds();
+    let report = stats.report(&facilities, &edges, &[smelt()], None);
+
+    // Starved consumers point at their producers; the busier one ranks first
+    let ranked: Vec<&str> = report.bottlenecks.iter().map(|b| b.instance_id.as_str()).collect();
+    assert_eq!(ranked, vec!["smelter", "slow"]);
+    assert_eq!(report.bottlenecks[0].score, 100.0);
+    assert_eq!(report.bottlenecks[1].score, 50.0);
+}
+
+#[test]
+fn test_compares_measured_against_plan() {
+    let (stats, facilities, edges) = sixty_seconds();
+    let plan = ProductionPlan {
+        target_items: vec!["item_iron_nugget".to_string(), "item_iron_cmpt".to_string()],
+        actual_rates: HashMap::from([
+            ("item_iron_nugget".to_string(), Rate::per_hour(3600.0)),
+            ("item_iron_cmpt".to_string(), Rate::per_minute(10.0)),
+        ]),
+        required_facilities: Vec::new(),
+        raw_materials: HashMap::new(),
+        total_power: 0.0,
+        total_area: 0.0,
+        belt_flows: Vec::new(),
+        port_bound: Vec::new(),
+        constraint_limited: false,
+        limiting_factor: None,
+    };
+    let report = stats.report(&facilities, &edges, &[smelt()], Some(&plan));
+
+    // Sorted by item id; measured comes back in the plan's unit
+    let cmpt = &report.rate_comparison[0];
+    assert_eq!(cmpt.item_id, "item_iron_cmpt");
+    assert_eq!(cmpt.measured, Rate::per_minute(0.0));
+    assert_eq!(cmpt.ratio, Some(0.0));
+
+    let nugget = &report.rate_comparison[1];
+    assert_eq!(nugget.predicted, Rate::per_hour(3600.0));
+    assert_eq!(nugget.measured, Rate::per_hour(2700.0));
+    assert_eq!(nugget.ratio, Some(0.75));
+}
@@ -0,0 +1,50 @@
+//! Lock helpers for state shared between command handlers and the simulation thread.
+//! A panic while a lock is held poisons it; with plain `.unwrap()` every later caller
+//! panics too. These take the guard anyway, log once and clear the poison, so one bad
+//! tick or command doesn't take the whole app down with it.
+
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+pub trait MutexExt<T> {
+    /// `lock()` that recovers the data from a poisoned mutex
+    fn lock_or_recover(&self) -> MutexGuard<'_, T>;
+}
+
+pub trait RwLockExt<T> {
+    /// `read()` that recovers the data from a poisoned lock
+    fn read_or_recover(&self) -> RwLockReadGuard<'_, T>;
+    /// `write()` that recovers the data from a poisoned lock
+    fn write_or_recover(&self) -> RwLockWriteGuard<'_, T>;
+}
+
+impl<T> MutexExt<T> for Mutex<T> {
+    fn lock_or_recover(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(|poisoned| {
+            recovered(std::any::type_name::<T>());
+            self.clear_poison();
+            PoisonError::into_inner(poisoned)
+        })
+    }
+}
+
+impl<T> RwLockExt<T> for RwLock<T> {
+    fn read_or_recover(&self) -> RwLockReadGuard<'_, T> {
+        self.read().unwrap_or_else(|poisoned| {
+            recovered(std::any::type_name::<T>());
+            self.clear_poison();
+            PoisonError::into_inner(poisoned)
+        })
+    }
+
+    fn write_or_recover(&self) -> RwLockWriteGuard<'_, T> {
+        self.write().unwrap_or_else(|poisoned| {
+            recovered(std::any::type_name::<T>());
+            self.clear_poison();
+            PoisonError::into_inner(poisoned)
+        })
+    }
+}
+
+fn recovered(type_name: &str) {
+    tracing::error!(state = type_name, "lock was poisoned by an earlier panic; continuing with its last state");
+}
@@ -0,0 +1,37 @@
+use crate::sync::{MutexExt, RwLockExt};
+use std::sync::{Arc, Mutex, RwLock};
+
+#[test]
+fn test_recovers_poisoned_mutex() {
+    let shared = Arc::new(Mutex::new(vec![1, 2]));
+    let inner = Arc::clone(&shared);
+    let _ = std::thread::spawn(move || {
+        let mut data = inner.lock().unwrap();
+        data.push(3);
+        panic!("tick failed");
+    })
+    .join();
+    assert!(shared.is_poisoned());
+
+    // Keeps whatever the panicking thread left behind, and later plain locks work again
+    assert_eq!(*shared.lock_or_recover(), vec![1, 2, 3]);
+    assert!(!shared.is_poisoned());
+    assert!(shared.lock().is_ok());
+}
+
+#[test]
+fn test_recovers_poisoned_rwlock() {
+    let shared = Arc::new(RwLock::new(0));
+    let inner = Arc::clone(&shared);
+    let _ = std::thread::spawn(move || {
+        let mut value = inner.write().unwrap();
+        *value = 7;
+        panic!("command failed");
+    })
+    .join();
+    assert!(shared.is_poisoned());
+
+    assert_eq!(*shared.read_or_recover(), 7);
+    *shared.write_or_recover() += 1;
+    assert_eq!(*shared.read().unwrap(), 8);
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// Character for a buildable cell in a plate definition map
+const BUILDABLE_CHAR: char = '.';
+/// Character for a blocked cell in a plate definition map
+const BLOCKED_CHAR: char = '#';
+
+/// What one plate cell is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TerrainCell {
+    Buildable,
+    /// Obstacle or outside an irregular plate edge; nothing can be built here
+    Blocked,
+    /// Buildable deposit; a miner covering it extracts `item_id`
+    ResourceNode { item_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceNode {
+    pub x: i32,
+    pub y: i32,
+    pub item_id: String,
+}
+
+/// Terrain of a plate. Stored sparsely: every cell not listed is buildable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Terrain {
+    #[serde(default)]
+    pub blocked: BTreeSet<(i32, i32)>,
+    #[serde(default)]
+    pub resources: Vec<ResourceNode>,
+}
+
+impl Terrain {
+    pub fn is_empty(&self) -> bool {
+        self.blocked.is_empty() && self.resources.is_empty()
+    }
+
+    pub fn cell(&self, x: i32, y: i32) -> TerrainCell {
+        if self.is_blocked(x, y) {
+            TerrainCell::Blocked
+        } else if let Some(item_id) = self.resource_at(x, y) {
+            TerrainCell::ResourceNode { item_id: item_id.to_string() }
+        } else {
+            TerrainCell::Buildable
+        }
+    }
+
+    pub fn is_blocked(&self, x: i32, y: i32) -> bool {
+        self.blocked.contains(&(x, y))
+    }
+
+    pub fn resource_at(&self, x: i32, y: i32) -> Option<&str> {
+        self.resources.iter().find(|n| n.x == x && n.y == y).map(|n| n.item_id.as_str())
+    }
+
+    /// First blocked cell of the area, row by row
+    pub fn blocked_in(&self, x: i32, y: i32, w: u32, h: u32) -> Option<(i32, i32)> {
+        self.blocked.range((x, y)..(x + w as i32, y))
+            .copied()
+            .filter(|&(_, cy)| cy >= y && cy < y + h as i32)
+            .min_by_key(|&(cx, cy)| (cy, cx))
+    }
+
+    /// Item of the first resource node the area covers, row by row
+    pub fn resource_in(&self, x: i32, y: i32, w: u32, h: u32) -> Option<&str> {
+        self.resources.iter()
+            .filter(|n| n.x >= x && n.y >= y && n.x < x + w as i32 && n.y < y + h as i32)
+            .min_by_key(|n| (n.y, n.x))
+            .map(|n| n.item_id.as_str())
+    }
+}
+
+/// A build site drawn as a character map, one string per row:
+///
+/// ```json
+/// { "name": "Valley", "rows": ["..##", "..o.", "."], "resources": { "o": "item_iron_ore" } }
+/// ```
+///
+/// `.` is buildable, `#` blocked, and characters listed in `resources` are deposits of
+/// that item. The plate is as wide as the longest row; cells past the end of a shorter
+/// row are blocked, which is how irregular edges are drawn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlateDefinition {
+    pub name: String,
+    pub rows: Vec<String>,
+    #[serde(default)]
+    pub resources: BTreeMap<String, String>,
+}
+
+impl PlateDefinition {
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| format!("Invalid plate definition: {}", e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_json(&text)
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        let width = self.rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+        (width as u32, self.rows.len() as u32)
+    }
+
+    pub fn terrain(&self) -> Result<Terrain, String> {
+        let mut legend: BTreeMap<char, &str> = BTreeMap::new();
+        for (key, item_id) in &self.resources {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c != BUILDABLE_CHAR && c != BLOCKED_CHAR => {
+                    legend.insert(c, item_id);
+                }
+                _ => return Err(format!("Resource key {:?} must be a single character other than '{}' and '{}'", key, BUILDABLE_CHAR, BLOCKED_CHAR)),
+            }
+        }
+
+        let (width, _) = self.size();
+        let mut terrain = Terrain::default();
+        for (y, row) in self.rows.iter().enumerate() {
+            let mut cells = row.chars().map(Some).chain(std::iter::repeat(None));
+            for x in 0..width as usize {
+                let (x, y) = (x as i32, y as i32);
+                match cells.next().flatten() {
+                    Some(BUILDABLE_CHAR) => {}
+                    Some(BLOCKED_CHAR) | None => {
+                        terrain.blocked.insert((x, y));
+                    }
+                    Some(c) => {
+                        let item_id = legend.get(&c)
+                            .ok_or_else(|| format!("Unknown terrain character '{}' at ({}, {})", c, x, y))?;
+                        terrain.resources.push(ResourceNode { x, y, item_id: item_id.to_string() });
+                    }
+                }
+            }
+        }
+        Ok(terrain)
+    }
+}
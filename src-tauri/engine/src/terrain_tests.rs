@@ -0,0 +1,132 @@
+use crate::data_loader::DataLoader;
+use crate::facility::PlacedFacility;
+use crate::grid::PlacementIssue;
+use crate::grid_edit::GridEdit;
+use crate::layout_generator::{LayoutConstraints, LayoutGenerator};
+use crate::logistics_engine::LogisticsEngine;
+use crate::plates::PlateSet;
+use crate::project::ProjectFile;
+use crate::terrain::{PlateDefinition, TerrainCell};
+
+const MINER: &str = "item_port_miner_1";
+
+/// 16x12 site: a rock at (12..14, 0..2), an ore deposit at (11, 1) and a cut-off corner
+/// at the bottom right
+fn quarry() -> PlateDefinition {
+    let mut rows = vec![
+        "............###.".to_string(),
+        "...........o###.".to_string(),
+        "............###.".to_string(),
+    ];
+    rows.extend(std::iter::repeat_n("................".to_string(), 7));
+    rows.extend(std::iter::repeat_n("............".to_string(), 2));
+    PlateDefinition::from_json(&serde_json::json!({
+        "name": "Quarry",
+        "rows": rows,
+        "resources": { "o": "item_iron_ore" },
+    }).to_string()).unwrap()
+}
+
+#[test]
+fn test_plate_definition_parsing() {
+    let definition = quarry();
+    assert_eq!(definition.size(), (16, 12));
+    let terrain = definition.terrain().unwrap();
+    assert_eq!(terrain.cell(0, 0), TerrainCell::Buildable);
+    assert_eq!(terrain.cell(12, 1), TerrainCell::Blocked);
+    assert_eq!(terrain.cell(11, 1), TerrainCell::ResourceNode { item_id: "item_iron_ore".to_string() });
+    // Short rows leave the rest of the line blocked
+    assert_eq!(terrain.cell(12, 11), TerrainCell::Blocked);
+    assert_eq!(terrain.cell(11, 11), TerrainCell::Buildable);
+    assert_eq!(terrain.blocked_in(8, 0, 6, 3), Some((12, 0)));
+    assert_eq!(terrain.blocked_in(0, 0, 12, 10), None);
+
+    let unknown = PlateDefinition::from_json(r#"{ "name": "Bad", "rows": [".x"] }"#).unwrap();
+    assert!(unknown.terrain().unwrap_err().contains("(1, 0)"));
+    let bad_key = PlateDefinition::from_json(r##"{ "name": "Bad", "rows": ["."], "resources": { "#": "item_iron_ore" } }"##).unwrap();
+    assert!(bad_key.terrain().is_err());
+}
+
+#[test]
+fn test_blocked_cells_reject_placement() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let mut plates = PlateSet::new(&DataLoader::load_config().unwrap());
+    plates.add_plate_from_definition(&quarry(), &DataLoader::load_config().unwrap()).unwrap();
+    let grid = &mut plates.plates[1].grid;
+
+    assert!(grid.is_area_clear(9, 0, 3, 3));
+    assert!(!grid.is_area_clear(10, 0, 3, 3));
+    let edit = GridEdit::PlaceFacility { facility: PlacedFacility::new("furnace", "item_port_furnance_1", 11, 8) };
+    assert!(grid.apply_edit(edit, &geometry).is_err());
+
+    let issues = grid.validate_layout(&[PlacedFacility::new("furnace", "item_port_furnance_1", 11, 0)], &[], &geometry);
+    assert_eq!(issues, vec![PlacementIssue::BlockedTerrain { instance_id: "furnace".to_string(), x: 12, y: 0 }]);
+}
+
+#[test]
+fn test_layout_generator_avoids_blocked_cells() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let definition = PlateDefinition::from_json(&serde_json::json!({
+        "name": "Ridge",
+        // A wall right of the PAC with a single gap at the bottom
+        "rows": std::iter::repeat_n(format!("{}##{}", ".".repeat(12), ".".repeat(18)), 19)
+            .chain(std::iter::once(".".repeat(32)))
+            .collect::<Vec<_>>(),
+    }).to_string()).unwrap();
+    let terrain = definition.terrain().unwrap();
+    let generator = LayoutGenerator::new(LayoutConstraints {
+        plate_width: 32,
+        plate_height: 20,
+        power_source_type: "hub_pac_main".to_string(),
+        power_source_x: 0,
+        power_source_y: 0,
+        max_power_budget: None,
+        terrain: terrain.clone(),
+    }, geometry.clone());
+
+    let required = vec![("item_port_furnance_1".to_string(), "item_port_furnance_1".to_string(), 6.0)];
+    let layouts = generator.generate_layouts(required, &[], 1);
+    let placed = &layouts[0].facilities;
+    assert_eq!(placed.len(), 7);
+    for f in placed {
+        let (w, h) = crate::geometry::footprint(&geometry, &f.facility_id, f.rotation as u32);
+        assert_eq!(terrain.blocked_in(f.x, f.y, w, h), None, "{} sits on blocked terrain", f.instance_id);
+    }
+}
+
+#[test]
+fn test_miner_extracts_resource_node() {
+    let geometry = DataLoader::load_geometry().unwrap();
+    let recipes = DataLoader::load_recipes().unwrap();
+    let config = DataLoader::load_config().unwrap();
+    let mut plates = PlateSet::new(&config);
+    plates.add_plate_from_definition(&quarry(), &config).unwrap();
+    let grid = &mut plates.plates[1].grid;
+
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("pac", "hub_pac_main", 0, 0) }, &geometry).unwrap();
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("miner", MINER, 9, 0) }, &geometry).unwrap();
+    grid.apply_edit(GridEdit::PlaceFacility { facility: PlacedFacility::new("idle", MINER, 9, 4) }, &geometry).unwrap();
+    assert_eq!(grid.mined_items.get("miner").map(String::as_str), Some("item_iron_ore"));
+    assert!(!grid.mined_items.contains_key("idle"));
+
+    for _ in 0..200 {
+        LogisticsEngine::step(grid, &recipes, 0.05);
+    }
+    let ore: u32 = grid.placed_facilities[1].output_buffer.iter()
+        .filter(|s| s.item_id == "item_iron_ore")
+        .map(|s| s.quantity)
+        .sum();
+    assert_eq!(ore, 5);
+    assert!(grid.placed_facilities[2].output_buffer.iter().all(|s| s.is_empty()));
+    assert!((grid.stats.produced_per_minute()["item_iron_ore"] - 30.0).abs() < 1e-6);
+
+    // Moving the miner off the node stops it; the terrain survives a save
+    grid.apply_edit(GridEdit::MoveFacility { instance_id: "miner".to_string(), x: 5, y: 9 }, &geometry).unwrap();
+    assert!(grid.mined_items.is_empty());
+    grid.apply_edit(GridEdit::MoveFacility { instance_id: "miner".to_string(), x: 9, y: 0 }, &geometry).unwrap();
+
+    let project = ProjectFile::from_json(&ProjectFile::capture(&plates, &[]).to_json().unwrap()).unwrap();
+    let restored = project.restore(&config, &geometry).unwrap();
+    assert_eq!(restored.plates[1].grid.terrain, plates.plates[1].grid.terrain);
+    assert_eq!(restored.plates[1].grid.mined_items.get("miner").map(String::as_str), Some("item_iron_ore"));
+}
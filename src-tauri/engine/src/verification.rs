@@ -0,0 +1,160 @@
+use crate::facility::PlacedFacility;
+use crate::geometry;
+use crate::grid::GridState;
+use crate::layout_generator::LayoutCandidate;
+use crate::logistics_engine::LogisticsEngine;
+use crate::rate::Rate;
+use crate::recipe::Recipe;
+use crate::recipe_solver::ProductionPlan;
+use crate::simulation_stats::{SimulationReport, SimulationStats};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationSettings {
+    /// Fixed step in seconds (0.05 = the frontend's 20 TPS loop)
+    pub dt: f64,
+    /// Time to let belts and buffers fill before measuring
+    pub warmup_seconds: f64,
+    /// Length of each measurement window
+    pub window_seconds: f64,
+    /// Give up on reaching steady state after this much simulated time
+    pub max_seconds: f64,
+    /// Allowed relative deviation between measured and planned rates
+    pub tolerance: f64,
+}
+
+impl Default for VerificationSettings {
+    fn default() -> Self {
+        Self {
+            dt: 0.05,
+            warmup_seconds: 60.0,
+            window_seconds: 120.0,
+            max_seconds: 1200.0,
+            tolerance: 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateCheck {
+    pub item_id: String,
+    /// Both in the unit the plan's target was requested in
+    pub planned: Rate,
+    pub measured: Rate,
+    pub relative_error: f64,
+    pub within_tolerance: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationResult {
+    pub passed: bool,
+    /// False when `max_seconds` ran out before two windows agreed
+    pub steady_state: bool,
+    pub simulated_seconds: f64,
+    pub checks: Vec<RateCheck>,
+    pub report: SimulationReport,
+}
+
+/// Headless harness: loads a routed layout into a grid, runs the deterministic
+/// simulation and checks the measured output against a `ProductionPlan`
+pub struct PlanVerifier {
+    recipes: Vec<Recipe>,
+    geometry: serde_json::Value,
+    config: serde_json::Value,
+    settings: VerificationSettings,
+}
+
+impl PlanVerifier {
+    pub fn new(
+        recipes: Vec<Recipe>,
+        geometry: serde_json::Value,
+        config: serde_json::Value,
+        settings: VerificationSettings,
+    ) -> Self {
+        Self { recipes, geometry, config, settings }
+    }
+
+    /// Builds a grid holding the candidate's facilities and edges, sized to fit the layout
+    pub fn load_candidate(&self, candidate: &LayoutCandidate) -> GridState {
+        let mut width = 1;
+        let mut height = 1;
+        for f in &candidate.facilities {
+            let (w, h) = geometry::footprint(&self.geometry, &f.facility_id, f.rotation.rem_euclid(360) as u32);
+            width = width.max(f.x.max(0) as u32 + w);
+            height = height.max(f.y.max(0) as u32 + h);
+        }
+
+        let mut grid = GridState::with_size(&self.config, width, height);
+        grid.placed_facilities = candidate.facilities.iter().enumerate().map(|(i, f)| PlacedFacility {
+            rotation: f.rotation.rem_euclid(360) as u32,
+            ..PlacedFacility::new(&f.resolved_instance_id(i), &f.facility_id, f.x, f.y)
+        }).collect();
+        grid.logistics_edges = candidate.edges.clone();
+        grid.resize_buffers(&self.geometry);
+        grid.rebuild_occupancy(&self.geometry);
+        grid.update_power_grid(&self.geometry);
+        grid
+    }
+
+    fn run_for(&self, grid: &mut GridState, seconds: f64) {
+        let steps = (seconds / self.settings.dt).round() as usize;
+        for _ in 0..steps {
+            LogisticsEngine::step(grid, &self.recipes, self.settings.dt);
+        }
+    }
+
+    /// Simulates until two consecutive windows agree (or `max_seconds` passes)
+    /// and compares the last window against `plan.actual_rates`
+    pub fn verify(&self, candidate: &LayoutCandidate, plan: &ProductionPlan) -> VerificationResult {
+        let settings = &self.settings;
+        let mut grid = self.load_candidate(candidate);
+        self.run_for(&mut grid, settings.warmup_seconds);
+
+        let mut previous: Option<HashMap<String, f64>> = None;
+        loop {
+            // Fresh counters per window; the span is doubled so nothing is evicted mid-window
+            grid.stats = SimulationStats::new(settings.window_seconds * 2.0);
+            self.run_for(&mut grid, settings.window_seconds);
+
+            let report = grid.stats.report(&grid.placed_facilities, &grid.logistics_edges, &self.recipes, Some(plan));
+            let rates: HashMap<String, f64> = report.rate_comparison.iter()
+                .map(|c| (c.item_id.clone(), c.measured.as_per_minute()))
+                .collect();
+
+            let steady_state = previous.as_ref().is_some_and(|prev| {
+                rates.iter().all(|(item_id, rate)| {
+                    let planned = plan.actual_rates.get(item_id).map_or(0.0, |r| r.as_per_minute()).max(1e-9);
+                    (rate - prev.get(item_id).copied().unwrap_or(0.0)).abs() / planned <= settings.tolerance
+                })
+            });
+
+            if steady_state || grid.sim_time >= settings.max_seconds {
+                let checks: Vec<RateCheck> = report.rate_comparison.iter().map(|c| {
+                    let (planned, measured) = (c.predicted.as_per_minute(), c.measured.as_per_minute());
+                    let relative_error = if planned > 0.0 {
+                        (measured - planned).abs() / planned
+                    } else {
+                        measured
+                    };
+                    RateCheck {
+                        item_id: c.item_id.clone(),
+                        planned: c.predicted,
+                        measured: c.measured,
+                        relative_error,
+                        within_tolerance: relative_error <= settings.tolerance,
+                    }
+                }).collect();
+
+                return VerificationResult {
+                    passed: steady_state && checks.iter().all(|c| c.within_tolerance),
+                    steady_state,
+                    simulated_seconds: grid.sim_time,
+                    checks,
+                    report,
+                };
+            }
+            previous = Some(rates);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::data_loader::DataLoader;
+use crate::layout_generator::{LayoutCandidate, PlacedFacilityLayout};
+use crate::logistics::LogisticsEdge;
+use crate::rate::Rate;
+use crate::recipe_solver::{ProductionPlan, RecipeSolver};
+use crate::verification::{PlanVerifier, VerificationSettings};
+use std::collections::HashMap;
+
+// Reference builds use the real database so recipe or balance changes show up here.
+
+fn place(instance_id: &str, facility_id: &str, x: i32, y: i32) -> PlacedFacilityLayout {
+    PlacedFacilityLayout {
+        instance_id: instance_id.to_string(),
+        facility_id: facility_id.to_string(),
+        x,
+        y,
+        rotation: 0,
+    }
+}
+
+fn candidate(facilities: Vec<PlacedFacilityLayout>, edges: Vec<LogisticsEdge>) -> LayoutCandidate {
+    LayoutCandidate {
+        id: "reference".to_string(),
+        facilities,
+        score: 0.0,
+        power_consumption: 0.0,
+        rates: HashMap::new(),
+        efficiency: 1.0,
+        limiting_factor: None,
+        edges,
+    }
+}
+
+fn plan_for(targets: Vec<(&str, f64)>) -> ProductionPlan {
+    let recipes = DataLoader::load_recipes().unwrap();
+    let facilities = DataLoader::load_facilities().unwrap()
+        .into_iter()
+        .map(|f| (f.id.clone(), f))
+        .collect();
+    let solver = RecipeSolver::new(recipes.iter().map(Into::into).collect(), facilities);
+    let targets = targets.into_iter().map(|(id, rate)| (id.to_string(), Rate::per_minute(rate))).collect();
+    solver.solve(targets, 32, 32).unwrap()
+}
+
+fn verifier() -> PlanVerifier {
+    PlanVerifier::new(
+        DataLoader::load_recipes().unwrap(),
+        DataLoader::load_geometry().unwrap(),
+        DataLoader::load_config().unwrap(),
+        VerificationSettings::default(),
+    )
+}
+
+#[test]
+fn test_single_furnace_matches_plan() {
+    // PAC -> Refining Unit -> PAC, 30 ore/min in, 30 nuggets/min out
+    let build = candidate(
+        vec![
+            place("pac", "hub_pac_main", 0, 0),
+            place("furnace", "item_port_furnance_1", 10, 0),
+        ],
+        vec![
+            LogisticsEdge::new("pac", "out_r1", "furnace", "in_1", "item_iron_ore"),
+            LogisticsEdge::new("furnace", "out_4", "pac", "in_t1", "placeholder"),
+        ],
+    );
+    let plan = plan_for(vec![("item_iron_nugget", 30.0)]);
+
+    let result = verifier().verify(&build, &plan);
+
+    assert!(result.steady_state);
+    assert!(result.passed, "{:?}", result.checks);
+    assert_eq!(result.checks.len(), 1);
+}
+
+#[test]
+fn test_two_stage_chain_matches_plan() {
+    // Ore -> nugget -> component
+    let build = candidate(
+        vec![
+            place("pac", "hub_pac_main", 0, 0),
+            place("furnace", "item_port_furnance_1", 10, 0),
+            place("fitting", "item_port_cmpt_mc_1", 0, 10),
+        ],
+        vec![
+            LogisticsEdge::new("pac", "out_r1", "furnace", "in_1", "item_iron_ore"),
+            LogisticsEdge::new("furnace", "out_4", "fitting", "in_1", "placeholder"),
+            LogisticsEdge::new("fitting", "out_4", "pac", "in_b1", "placeholder"),
+        ],
+    );
+    let plan = plan_for(vec![("item_iron_cmpt", 30.0)]);
+
+    let result = verifier().verify(&build, &plan);
+
+    assert!(result.passed, "{:?}", result.checks);
+}
+
+#[test]
+fn test_undersupplied_build_fails_verification() {
+    // Supply belt at half the rate the plan assumes
+    let build = candidate(
+        vec![
+            place("pac", "hub_pac_main", 0, 0),
+            place("furnace", "item_port_furnance_1", 10, 0),
+        ],
+        vec![
+            LogisticsEdge { throughput: 0.25, ..LogisticsEdge::new("pac", "out_r1", "furnace", "in_1", "item_iron_ore") },
+            LogisticsEdge::new("furnace", "out_4", "pac", "in_t1", "placeholder"),
+        ],
+    );
+    let plan = plan_for(vec![("item_iron_nugget", 30.0)]);
+
+    let result = verifier().verify(&build, &plan);
+
+    assert!(result.steady_state);
+    assert!(!result.passed);
+    let check = &result.checks[0];
+    assert!(check.measured.as_per_minute() > 14.0 && check.measured.as_per_minute() < 16.0);
+}
@@ -0,0 +1,8 @@
+//! Runs the engine as a local JSON-RPC service so dashboards and bots can drive
+//! the same operations as the desktop app. Each client works in its own session.
+
+pub mod session;
+pub mod rpc;
+
+#[cfg(test)]
+pub mod rpc_tests;
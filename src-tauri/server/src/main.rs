@@ -0,0 +1,138 @@
+//! Serves JSON-RPC 2.0 over HTTP POST on localhost.
+
+use endfield_engine::data_loader::DATABASE_PATH_VAR;
+use endfield_engine::logging::{LogOptions, Logging};
+use endfield_server::rpc::Service;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use tiny_http::{Header, Method, Response, Server, StatusCode};
+
+const USAGE: &str = "\
+Usage: endfield-server [--db <database.json>] [--port <port>] [--workers <n>] [--log-dir <dir>]
+
+Serves JSON-RPC 2.0 over HTTP POST on 127.0.0.1. Start with `create_session`,
+then pass the returned `session` id to every other call.
+
+Options:
+  --db <path>          Database to use instead of the default lookup
+  --port <port>        Port to listen on (default 7878)
+  --workers <n>        Request handler threads (default 4)
+  --log-dir <dir>      Also write daily-rotated JSON logs to this folder
+
+Log filter: ENDFIELD_LOG (e.g. info,endfield_engine::recipe_solver=debug)";
+
+const DEFAULT_PORT: u16 = 7878;
+const DEFAULT_WORKERS: usize = 4;
+
+#[derive(Debug)]
+struct Args {
+    port: u16,
+    workers: usize,
+    log_dir: Option<PathBuf>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args { port: DEFAULT_PORT, workers: DEFAULT_WORKERS, log_dir: None };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "-h" | "--help" => return Err(String::new()),
+                "--db" => std::env::set_var(DATABASE_PATH_VAR, value()?),
+                "--port" => {
+                    let port = value()?;
+                    parsed.port = port.parse().map_err(|_| format!("Invalid port {}", port))?;
+                }
+                "--workers" => {
+                    let workers = value()?;
+                    parsed.workers = workers.parse().ok().filter(|n| *n > 0)
+                        .ok_or_else(|| format!("--workers must be a positive number, got {}", workers))?;
+                }
+                "--log-dir" => parsed.log_dir = Some(PathBuf::from(value()?)),
+                other => return Err(format!("Unexpected argument {}", other)),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        // An empty message means --help was asked for
+        Err(message) if message.is_empty() => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut log_options = LogOptions::new("endfield-server");
+    log_options.directory = args.log_dir.clone();
+    let _logging = match Logging::init(log_options) {
+        Ok(logging) => logging,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Loopback only: sessions are not authenticated
+    let address = ("127.0.0.1", args.port);
+    let server = match Server::http(address) {
+        Ok(server) => Arc::new(server),
+        Err(e) => {
+            tracing::error!(port = args.port, error = %e, "failed to listen");
+            return ExitCode::FAILURE;
+        }
+    };
+    let service = match Service::new() {
+        Ok(service) => Arc::new(service),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load the database");
+            return ExitCode::FAILURE;
+        }
+    };
+    tracing::info!("Listening on http://127.0.0.1:{}", args.port);
+
+    let workers: Vec<_> = (0..args.workers).map(|_| {
+        let server = Arc::clone(&server);
+        let service = Arc::clone(&service);
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                serve(&service, request);
+            }
+        })
+    }).collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    ExitCode::SUCCESS
+}
+
+fn serve(service: &Service, mut request: tiny_http::Request) {
+    if *request.method() != Method::Post {
+        let response = Response::from_string("POST JSON-RPC requests to this endpoint\n")
+            .with_status_code(StatusCode(405))
+            .with_header(Header::from_bytes("Allow", "POST").unwrap());
+        let _ = request.respond(response);
+        return;
+    }
+
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().read_to_string(&mut body) {
+        let _ = request.respond(Response::from_string(format!("Failed to read request: {}\n", e)).with_status_code(StatusCode(400)));
+        return;
+    }
+
+    let _ = match service.handle(&body) {
+        Some(reply) => request.respond(Response::from_string(reply)
+            .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())),
+        // Notifications only
+        None => request.respond(Response::empty(StatusCode(204))),
+    };
+}
@@ -0,0 +1,365 @@
+use crate::session::Session;
+use endfield_engine::data_loader::DataLoader;
+use endfield_engine::error::EngineError;
+use endfield_engine::facility::PlacedFacility;
+use endfield_engine::grid_edit::GridEdit;
+use endfield_engine::live_simulation::PowerStatus;
+use endfield_engine::logistics::LogisticsEdge;
+use endfield_engine::rate::Rate;
+use endfield_engine::recipe::Recipe;
+use endfield_engine::sync::MutexExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+// JSON-RPC 2.0 error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// The engine rejected the call (bad edit, unsolvable targets, ...)
+pub const ENGINE_ERROR: i64 = -32000;
+pub const UNKNOWN_SESSION: i64 = -32001;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    /// The serialized `EngineError` (code, message, context) for engine failures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+}
+
+impl From<EngineError> for RpcError {
+    fn from(error: EngineError) -> Self {
+        Self { code: ENGINE_ERROR, message: error.to_string(), data: serde_json::to_value(&error).ok() }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    /// Absent for notifications, which get no response
+    id: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionParam {
+    session: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SolveParams {
+    target_items: Vec<(String, Rate)>, // (item_id, rate); bare numbers are per minute
+    plate_width: i32,
+    plate_height: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct LayoutParams {
+    target_items: Vec<(String, Rate)>,
+    plate_width: i32,
+    plate_height: i32,
+    #[serde(default = "default_candidates")]
+    num_candidates: usize,
+}
+
+fn default_candidates() -> usize {
+    5
+}
+
+#[derive(Debug, Deserialize)]
+struct TickParams {
+    #[serde(default = "default_ticks")]
+    ticks: u32,
+}
+
+fn default_ticks() -> u32 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+struct LayoutStateParams {
+    facilities: Vec<PlacedFacility>,
+    edges: Vec<LogisticsEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaceParams {
+    facility_id: String,
+    x: i32,
+    y: i32,
+    #[serde(default)]
+    rotation: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct MoveParams {
+    instance_id: String,
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RotateParams {
+    instance_id: String,
+    rotation: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstanceParams {
+    instance_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EdgeParams {
+    edge: LogisticsEdge,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyParams {
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlateParams {
+    name: String,
+    preset: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NameParams {
+    name: String,
+}
+
+/// Methods that act on a session's plates
+const SESSION_METHODS: &[&str] = &[
+    "solve", "solve_production", "generate_optimal_layouts", "tick_simulation",
+    "get_grid_state", "get_power_status", "get_simulation_report", "check_edges",
+    "update_simulation_state", "place_facility", "move_facility", "rotate_facility",
+    "remove_facility", "add_edge", "remove_edge", "undo", "redo", "get_history",
+    "list_plates", "add_plate", "select_plate", "remove_plate",
+];
+
+/// Engine commands over JSON-RPC 2.0. Method names and parameters follow the
+/// desktop app's Tauri commands; everything but `create_session` and
+/// `get_app_data` takes a `session` parameter naming the sandbox to act on.
+pub struct Service {
+    sessions: Mutex<HashMap<String, Arc<Mutex<Session>>>>,
+    next_session: AtomicU64,
+    recipes: Vec<Recipe>,
+}
+
+impl Service {
+    pub fn new() -> Result<Self, EngineError> {
+        Ok(Self {
+            sessions: Mutex::new(HashMap::new()),
+            next_session: AtomicU64::new(1),
+            recipes: DataLoader::load_recipes()?,
+        })
+    }
+
+    pub fn session_count(&self) -> usize {
+        self.sessions.lock_or_recover().len()
+    }
+
+    /// Handles a request body (single call or batch). `None` when nothing needs
+    /// to be sent back, i.e. the body held only notifications.
+    pub fn handle(&self, body: &str) -> Option<String> {
+        let response = match serde_json::from_str::<Value>(body) {
+            Err(e) => Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)))),
+            Ok(Value::Array(calls)) if calls.is_empty() => {
+                Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, "Empty batch")))
+            }
+            Ok(Value::Array(calls)) => {
+                let responses: Vec<Value> = calls.into_iter().filter_map(|call| self.handle_call(call)).collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(call) => self.handle_call(call),
+        };
+        response.map(|r| r.to_string())
+    }
+
+    fn handle_call(&self, call: Value) -> Option<Value> {
+        let request: Request = match serde_json::from_value(call) {
+            Ok(request) => request,
+            Err(e) => return Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, format!("Invalid request: {}", e)))),
+        };
+        if request.jsonrpc != "2.0" {
+            return Some(error_response(request.id.unwrap_or(Value::Null), RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\"")));
+        }
+
+        let result = self.call(&request.method, request.params);
+        let id = request.id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => {
+                tracing::warn!(method = %request.method, code = error.code, message = %error.message, "call failed");
+                error_response(id, error)
+            }
+        })
+    }
+
+    /// Runs one method. Public so embedders can skip the JSON-RPC envelope.
+    #[tracing::instrument(level = "debug", name = "rpc", skip(self, params))]
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "create_session" => {
+                let id = format!("session-{}", self.next_session.fetch_add(1, Ordering::Relaxed));
+                let session = Session::new(&DataLoader::load_config()?);
+                self.sessions.lock_or_recover().insert(id.clone(), Arc::new(Mutex::new(session)));
+                Ok(json!({ "session": id }))
+            }
+            "close_session" => {
+                let SessionParam { session } = parse(&params)?;
+                self.sessions.lock_or_recover().remove(&session)
+                    .ok_or_else(|| unknown_session(&session))?;
+                Ok(Value::Null)
+            }
+            "get_app_data" => Ok(json!({
+                "facilities": DataLoader::load_facilities()?,
+                "items": DataLoader::load_items()?,
+                "recipes": DataLoader::load_recipes()?,
+                "config": DataLoader::load_config()?,
+                "geometry": DataLoader::load_geometry()?,
+            })),
+            method if SESSION_METHODS.contains(&method) => {
+                let session = self.session(&params)?;
+                let mut session = session.lock_or_recover();
+                self.call_session(&mut session, method, &params)
+            }
+            other => Err(method_not_found(other)),
+        }
+    }
+
+    fn session(&self, params: &Value) -> Result<Arc<Mutex<Session>>, RpcError> {
+        let SessionParam { session } = parse(params)?;
+        self.sessions.lock_or_recover().get(&session).cloned()
+            .ok_or_else(|| unknown_session(&session))
+    }
+
+    fn call_session(&self, session: &mut Session, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "solve" | "solve_production" => {
+                let p: SolveParams = parse(params)?;
+                to_value(session.solve(p.target_items, p.plate_width, p.plate_height)?)
+            }
+            "generate_optimal_layouts" => {
+                let p: LayoutParams = parse(params)?;
+                to_value(session.generate_layouts(p.target_items, p.plate_width, p.plate_height, p.num_candidates)?)
+            }
+            "tick_simulation" => {
+                let p: TickParams = parse(params)?;
+                to_value(session.tick(&self.recipes, p.ticks))
+            }
+            "get_grid_state" => to_value(&session.plates.active().grid),
+            "get_power_status" => to_value(PowerStatus::of(&session.plates.active().grid)),
+            "get_simulation_report" => {
+                let grid = &session.plates.active().grid;
+                to_value(grid.stats.report(&grid.placed_facilities, &grid.logistics_edges, &self.recipes, session.last_plan.as_ref()))
+            }
+            "check_edges" => {
+                let geometry = DataLoader::load_geometry()?;
+                to_value(session.plates.active().grid.check_edges(&geometry, &self.recipes))
+            }
+            "update_simulation_state" => {
+                let p: LayoutStateParams = parse(params)?;
+                let plate = session.plates.active_mut();
+                let geometry = DataLoader::load_geometry()?;
+                match plate.grid.apply_layout(p.facilities, p.edges, &geometry) {
+                    // Full resync: recorded edits no longer line up with the grid
+                    Ok(()) => {
+                        plate.history.clear();
+                        Ok(Value::Null)
+                    }
+                    Err(issues) => Err(EngineError::RejectedLayout { issues }.into()),
+                }
+            }
+            "place_facility" => {
+                let p: PlaceParams = parse(params)?;
+                let facility = PlacedFacility { rotation: p.rotation, ..PlacedFacility::new("", &p.facility_id, p.x, p.y) };
+                to_value(session.apply_edit(GridEdit::PlaceFacility { facility })?)
+            }
+            "move_facility" => {
+                let MoveParams { instance_id, x, y } = parse(params)?;
+                to_value(session.apply_edit(GridEdit::MoveFacility { instance_id, x, y })?)
+            }
+            "rotate_facility" => {
+                let RotateParams { instance_id, rotation } = parse(params)?;
+                to_value(session.apply_edit(GridEdit::RotateFacility { instance_id, rotation })?)
+            }
+            "remove_facility" => {
+                let InstanceParams { instance_id } = parse(params)?;
+                to_value(session.apply_edit(GridEdit::RemoveFacility { instance_id })?)
+            }
+            "add_edge" => {
+                let EdgeParams { edge } = parse(params)?;
+                to_value(session.apply_edit(GridEdit::AddEdge { edge })?)
+            }
+            "remove_edge" => {
+                let KeyParams { key } = parse(params)?;
+                to_value(session.apply_edit(GridEdit::RemoveEdge { key })?)
+            }
+            "undo" => to_value(session.undo()?),
+            "redo" => to_value(session.redo()?),
+            "get_history" => to_value(session.plates.active().history.summary()),
+            "list_plates" => to_value(session.plates.summary()),
+            "add_plate" => {
+                let p: PlateParams = parse(params)?;
+                let config = DataLoader::load_config()?;
+                let (width, height) = match &p.preset {
+                    Some(preset) => endfield_engine::plates::PlateSet::preset_size(&config, preset)
+                        .ok_or_else(|| EngineError::MissingPreset { preset: preset.clone() })?,
+                    None => (p.width.unwrap_or(32), p.height.unwrap_or(32)),
+                };
+                session.plates.add_plate(&p.name, width, height, &config)?;
+                Ok(Value::Null)
+            }
+            "select_plate" => {
+                let NameParams { name } = parse(params)?;
+                session.plates.select(&name)?;
+                to_value(&session.plates.active().grid)
+            }
+            "remove_plate" => {
+                let NameParams { name } = parse(params)?;
+                session.plates.remove_plate(&name)?;
+                Ok(Value::Null)
+            }
+            other => Err(method_not_found(other)),
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: &Value) -> Result<T, RpcError> {
+    T::deserialize(params).map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(ENGINE_ERROR, format!("Failed to serialize result: {}", e)))
+}
+
+fn method_not_found(method: &str) -> RpcError {
+    RpcError::new(METHOD_NOT_FOUND, format!("Method {} not found", method))
+}
+
+fn unknown_session(session: &str) -> RpcError {
+    RpcError::new(UNKNOWN_SESSION, format!("Session {} not found", session))
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
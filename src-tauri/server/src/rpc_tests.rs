@@ -0,0 +1,90 @@
+use crate::rpc::{Service, INVALID_PARAMS, METHOD_NOT_FOUND, PARSE_ERROR, UNKNOWN_SESSION};
+use endfield_engine::data_loader::DATABASE_PATH_VAR;
+use serde_json::{json, Value};
+
+/// Service reading the repository's database.json, wherever the tests run from
+fn service() -> Service {
+    std::env::set_var(DATABASE_PATH_VAR, concat!(env!("CARGO_MANIFEST_DIR"), "/../../database.json"));
+    Service::new().unwrap()
+}
+
+fn create_session(service: &Service) -> String {
+    let created = service.call("create_session", Value::Null).unwrap();
+    created["session"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_sessions_have_separate_grids() {
+    let service = service();
+    let a = create_session(&service);
+    let b = create_session(&service);
+    assert_ne!(a, b);
+
+    let change = service.call("place_facility", json!({ "session": a, "facility_id": "hub_pac_main", "x": 0, "y": 0 })).unwrap();
+    assert_eq!(change["placed"][0]["instance_id"], "hub_pac_main_0");
+
+    let grid_a = service.call("get_grid_state", json!({ "session": a })).unwrap();
+    let grid_b = service.call("get_grid_state", json!({ "session": b })).unwrap();
+    assert_eq!(grid_a["placed_facilities"].as_array().unwrap().len(), 1);
+    assert!(grid_b["placed_facilities"].as_array().unwrap().is_empty());
+
+    // Undo only reaches the session's own history
+    assert_eq!(service.call("undo", json!({ "session": b })).unwrap(), json!([]));
+    service.call("undo", json!({ "session": a })).unwrap();
+    let grid_a = service.call("get_grid_state", json!({ "session": a })).unwrap();
+    assert!(grid_a["placed_facilities"].as_array().unwrap().is_empty());
+
+    service.call("close_session", json!({ "session": a })).unwrap();
+    assert_eq!(service.session_count(), 1);
+    let err = service.call("get_grid_state", json!({ "session": a })).unwrap_err();
+    assert_eq!(err.code, UNKNOWN_SESSION);
+}
+
+#[test]
+fn test_envelope_errors_and_batches() {
+    let service = service();
+
+    let reply: Value = serde_json::from_str(&service.handle("{not json").unwrap()).unwrap();
+    assert_eq!(reply["error"]["code"], PARSE_ERROR);
+
+    let reply: Value = serde_json::from_str(&service.handle(r#"{"jsonrpc":"2.0","id":1,"method":"fly"}"#).unwrap()).unwrap();
+    assert_eq!(reply["id"], 1);
+    assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+
+    // Notifications get no reply, even inside a batch
+    assert!(service.handle(r#"{"jsonrpc":"2.0","method":"create_session"}"#).is_none());
+    let batch = r#"[
+        {"jsonrpc":"2.0","method":"create_session"},
+        {"jsonrpc":"2.0","id":"a","method":"create_session"},
+        {"jsonrpc":"2.0","id":"b","method":"tick_simulation","params":{}}
+    ]"#;
+    let reply: Value = serde_json::from_str(&service.handle(batch).unwrap()).unwrap();
+    let replies = reply.as_array().unwrap();
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0]["result"]["session"], "session-3");
+    assert_eq!(replies[1]["error"]["code"], INVALID_PARAMS);
+    assert_eq!(service.session_count(), 3);
+}
+
+#[test]
+fn test_ticks_advance_the_simulation() {
+    let service = service();
+    let session = create_session(&service);
+    service.call("place_facility", json!({ "session": session, "facility_id": "hub_pac_main", "x": 0, "y": 0 })).unwrap();
+    service.call("place_facility", json!({ "session": session, "facility_id": "item_port_furnance_1", "x": 10, "y": 0 })).unwrap();
+    service.call("add_edge", json!({ "session": session, "edge": {
+        "from_instance_id": "hub_pac_main_0",
+        "from_port_id": "out_r1",
+        "to_instance_id": "item_port_furnance_1_0",
+        "to_port_id": "in_1",
+        "item_id": "item_iron_ore",
+        "throughput": 10.0,
+    } })).unwrap();
+
+    // Back-to-back ticks are fixed steps, not the few microseconds of wall clock between them
+    let facilities = service.call("tick_simulation", json!({ "session": session, "ticks": 100 })).unwrap();
+    let furnace = &facilities[1];
+    assert_eq!(furnace["instance_id"], "item_port_furnance_1_0");
+    assert_eq!(furnace["output_buffer"][0]["item_id"], "item_iron_nugget");
+    assert!(furnace["output_buffer"][0]["quantity"].as_u64().unwrap() > 0);
+}
@@ -0,0 +1,100 @@
+use endfield_engine::data_loader::DataLoader;
+use endfield_engine::error::EngineError;
+use endfield_engine::facility::PlacedFacility;
+use endfield_engine::grid_edit::{GridChange, GridEdit};
+use endfield_engine::layout_generator::{LayoutCandidate, LayoutConstraints, LayoutGenerator};
+use endfield_engine::live_simulation::SimulationClock;
+use endfield_engine::plates::PlateSet;
+use endfield_engine::rate::Rate;
+use endfield_engine::recipe::Recipe;
+use endfield_engine::recipe_solver::{ProductionPlan, RecipeSolver};
+
+/// One client's sandbox: its own plates (and so its own grids and undo history)
+/// and the last plan it solved. Mirrors the desktop app's `AppState`.
+pub struct Session {
+    pub plates: PlateSet,
+    pub last_plan: Option<ProductionPlan>,
+    pub targets: Vec<(String, Rate)>, // Last requested (item_id, rate)
+    /// Tick length; the same simulated seconds per tick as the desktop loop
+    pub clock: SimulationClock,
+}
+
+impl Session {
+    pub fn new(config: &serde_json::Value) -> Self {
+        Self {
+            plates: PlateSet::new(config),
+            last_plan: None,
+            targets: Vec::new(),
+            clock: SimulationClock::from_config(config),
+        }
+    }
+
+    /// Solves for the targets; the plan becomes the one simulation reports compare against
+    pub fn solve(&mut self, target_items: Vec<(String, Rate)>, plate_width: i32, plate_height: i32) -> Result<ProductionPlan, EngineError> {
+        let plan = RecipeSolver::from_database()?.solve(target_items.clone(), plate_width, plate_height)?;
+        self.last_plan = Some(plan.clone());
+        self.targets = target_items;
+        Ok(plan)
+    }
+
+    /// Solves and lays the plan out around the active plate's terrain; the terrain counts
+    /// toward the space the plan may use
+    pub fn generate_layouts(
+        &mut self,
+        target_items: Vec<(String, Rate)>,
+        plate_width: i32,
+        plate_height: i32,
+        num_candidates: usize,
+    ) -> Result<Vec<LayoutCandidate>, EngineError> {
+        let mut constraints = LayoutConstraints::for_plate(plate_width, plate_height);
+        constraints.terrain = self.plates.active().grid.terrain.clone();
+        let plan = RecipeSolver::from_database()?.solve_for_layout(target_items.clone(), &constraints)?;
+        self.last_plan = Some(plan.clone());
+        self.targets = target_items;
+        let generator = LayoutGenerator::new(constraints, DataLoader::load_geometry()?);
+        let required = plan.required_facilities.iter()
+            .map(|req| (req.facility_id.clone(), req.facility_type.clone(), req.count))
+            .collect();
+        let rates: Vec<(String, Rate)> = plan.actual_rates.iter().map(|(k, v)| (k.clone(), *v)).collect();
+
+        let mut candidates = generator.generate_layouts(required, &rates, num_candidates);
+        for candidate in &mut candidates {
+            candidate.limiting_factor = plan.limiting_factor.clone();
+        }
+        if candidates.is_empty() {
+            return Err(EngineError::infeasible("No valid layouts could be generated within the given constraints. Try a larger board."));
+        }
+        Ok(candidates)
+    }
+
+    /// Advances every plate by `ticks` fixed simulation ticks and returns the active plate's facilities
+    pub fn tick(&mut self, recipes: &[Recipe], ticks: u32) -> Vec<PlacedFacility> {
+        let dt = self.clock.step_seconds();
+        for _ in 0..ticks {
+            self.plates.step(recipes, dt);
+        }
+        self.plates.active().grid.placed_facilities.clone()
+    }
+
+    /// Applies one edit to the active plate as its own undo step
+    pub fn apply_edit(&mut self, edit: GridEdit) -> Result<GridChange, EngineError> {
+        let plate = self.plates.active_mut();
+        let geometry = DataLoader::load_geometry()?;
+        let label = edit.describe();
+        let mut changes = plate.history.apply(&mut plate.grid, &label, vec![edit], &geometry)?;
+        Ok(changes.pop().unwrap_or_default())
+    }
+
+    /// Empty list when there was nothing to undo
+    pub fn undo(&mut self) -> Result<Vec<GridChange>, EngineError> {
+        let plate = self.plates.active_mut();
+        let geometry = DataLoader::load_geometry()?;
+        Ok(plate.history.undo(&mut plate.grid, &geometry)?.unwrap_or_default())
+    }
+
+    pub fn redo(&mut self) -> Result<Vec<GridChange>, EngineError> {
+        let plate = self.plates.active_mut();
+        let geometry = DataLoader::load_geometry()?;
+        Ok(plate.history.redo(&mut plate.grid, &geometry)?.unwrap_or_default())
+    }
+}
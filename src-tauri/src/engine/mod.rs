@@ -1,13 +0,0 @@
-pub mod facility;
-pub mod item;
-pub mod recipe;
-pub mod grid;
-pub mod logistics;
-pub mod data_loader;
-pub mod optimizer;
-pub mod power_grid;
-pub mod recipe_solver;
-pub mod layout_generator;
-pub mod logistics_engine; // NEW
-#[cfg(test)]
-pub mod recipe_solver_tests;
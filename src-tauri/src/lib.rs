@@ -1,15 +1,37 @@
-pub mod engine;
+pub use endfield_engine as engine;
 
-use crate::engine::grid::GridState;
+use crate::engine::error::EngineError;
+use crate::engine::live_simulation::{SimulationClock, SimulationSnapshot};
+use crate::engine::logging::{LogOptions, Logging};
 use crate::engine::optimizer::Optimizer;
-use tauri::State;
-use std::sync::Mutex;
+use crate::engine::sync::RwLockExt;
+use tauri::{Emitter, Manager, State};
+use std::panic::AssertUnwindSafe;
+use std::sync::{mpsc, RwLock};
+use std::time::Instant;
 use serde::{Deserialize, Serialize};
 
+/// Event carrying a `SimulationDelta` after each background tick that changed something
+const SIMULATION_EVENT: &str = "simulation-delta";
+
+/// Shared between command handlers and the simulation thread. Read-mostly state sits
+/// behind `RwLock`s so UI queries don't queue behind each other, and every lock goes
+/// through `sync::RwLockExt` so a panic in one command doesn't poison the rest.
 struct AppState {
-    grid: Mutex<GridState>,
+    plates: RwLock<crate::engine::plates::PlateSet>, // Edits go to the active plate
     optimizer: Option<Optimizer>,
     recipes: Vec<crate::engine::recipe::Recipe>,
+    last_plan: RwLock<Option<crate::engine::recipe_solver::ProductionPlan>>,
+    targets: RwLock<Vec<(String, crate::engine::rate::Rate)>>, // Last requested (item_id, rate)
+    clock: RwLock<SimulationClock>, // Paces the background simulation loop
+    /// Manual steps are handed to the simulation thread, the only place the simulation advances
+    simulation: mpsc::Sender<SimulationRequest>,
+}
+
+/// Work for the simulation thread from outside its own schedule
+enum SimulationRequest {
+    /// One fixed tick on every plate; replies with the active plate's facilities
+    Tick(mpsc::Sender<Result<Vec<crate::engine::facility::PlacedFacility>, EngineError>>),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,25 +44,25 @@ pub struct AppData {
 }
 
 #[tauri::command]
-fn get_app_data() -> AppData {
-    println!("DEBUG: get_app_data called");
+fn get_app_data() -> Result<AppData, EngineError> {
     let data = AppData {
-        facilities: crate::engine::data_loader::DataLoader::load_facilities(),
-        items: crate::engine::data_loader::DataLoader::load_items(),
-        recipes: crate::engine::data_loader::DataLoader::load_recipes(),
-        config: crate::engine::data_loader::DataLoader::load_config(),
-        geometry: crate::engine::data_loader::DataLoader::load_geometry(),
+        facilities: crate::engine::data_loader::DataLoader::load_facilities()?,
+        items: crate::engine::data_loader::DataLoader::load_items()?,
+        recipes: crate::engine::data_loader::DataLoader::load_recipes()?,
+        config: crate::engine::data_loader::DataLoader::load_config()?,
+        geometry: crate::engine::data_loader::DataLoader::load_geometry()?,
     };
-    println!("DEBUG: Config loaded: {:?}", data.config);
-    println!("DEBUG: Geometry items: {}", data.geometry.as_array().map_or(0, |a| a.len()));
-    data
+    tracing::debug!(
+        facilities = data.facilities.len(),
+        geometry = data.geometry.as_array().map_or(0, |a| a.len()),
+        "app data loaded"
+    );
+    Ok(data)
 }
 
 #[tauri::command]
-fn get_grid_state(state: State<'_, AppState>) -> GridState {
-    println!("DEBUG: get_grid_state called");
-    let grid = state.grid.lock().unwrap();
-    serde_json::from_str(&serde_json::to_string(&*grid).unwrap()).unwrap()
+fn get_grid_state(state: State<'_, AppState>) -> crate::engine::grid::GridState {
+    state.plates.read_or_recover().active().grid.clone()
 }
 
 #[tauri::command]
@@ -48,91 +70,56 @@ fn update_simulation_state(
     state: State<'_, AppState>,
     facilities: Vec<crate::engine::facility::PlacedFacility>,
     edges: Vec<crate::engine::logistics::LogisticsEdge>,
-) {
-    println!("DEBUG: update_simulation_state called with {} facilities", facilities.len());
-    let mut grid = state.grid.lock().unwrap();
-    grid.placed_facilities = facilities;
-    grid.logistics_edges = edges;
-    
-    // Recalculate power grid
-    let geometry = crate::engine::data_loader::DataLoader::load_geometry();
-    grid.update_power_grid(&geometry);
+) -> Result<(), EngineError> {
+    tracing::debug!(facilities = facilities.len(), edges = edges.len(), "layout sync");
+    let mut plates = state.plates.write_or_recover();
+    let plate = plates.active_mut();
+
+    // Backend is the authority: bad layouts are rejected, occupancy and power are rebuilt
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+    let result = plate.grid.apply_layout(facilities, edges, &geometry)
+        .map_err(|issues| EngineError::RejectedLayout { issues });
+    match &result {
+        // Full resync: recorded edits no longer line up with the grid
+        Ok(()) => plate.history.clear(),
+        Err(e) => tracing::warn!(error = %e, context = %e.context(), "layout sync rejected"),
+    }
+    result
 }
 
 #[tauri::command]
-fn get_power_status(state: State<'_, AppState>) -> serde_json::Value {
-    // Silent for polling but useful for initial debug
-    // println!("DEBUG: get_power_status called");
-    let grid = state.grid.lock().unwrap();
-    serde_json::json!({
-        "total_generation": grid.power_grid.total_generation,
-        "total_consumption": grid.power_grid.total_consumption,
-        "power_balance": grid.power_grid.get_power_balance(),
-        "powered_count": grid.power_grid.powered_facilities.len(),
-    })
+fn get_power_status(state: State<'_, AppState>) -> crate::engine::live_simulation::PowerStatus {
+    let plates = state.plates.read_or_recover();
+    crate::engine::live_simulation::PowerStatus::of(&plates.active().grid)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct GenerateLayoutsRequest {
-    target_items: Vec<(String, f64)>, // (item_id, rate_per_minute)
+    target_items: Vec<(String, crate::engine::rate::Rate)>, // (item_id, rate); bare numbers are per minute
     plate_width: i32,
     plate_height: i32,
     num_candidates: usize,
 }
 
+/// Runs the recipe solver against the current database and makes the plan the one
+/// simulation reports and `export_plan` refer to
+fn solve_plan(state: &AppState, target_items: Vec<(String, crate::engine::rate::Rate)>, constraints: &crate::engine::layout_generator::LayoutConstraints) -> Result<crate::engine::recipe_solver::ProductionPlan, EngineError> {
+    let plan = crate::engine::recipe_solver::RecipeSolver::from_database()?
+        .solve_for_layout(target_items.clone(), constraints)?;
+    *state.last_plan.write_or_recover() = Some(plan.clone());
+    *state.targets.write_or_recover() = target_items;
+    Ok(plan)
+}
+
 #[tauri::command]
-fn generate_optimal_layouts(request: GenerateLayoutsRequest) -> Result<Vec<crate::engine::layout_generator::LayoutCandidate>, String> {
-    println!("DEBUG: generate_optimal_layouts called for {} target items", request.target_items.len());
-    
-    // Load data
-    let facilities_vec = crate::engine::data_loader::DataLoader::load_facilities();
-    let recipes_vec = crate::engine::data_loader::DataLoader::load_recipes();
-    let geometry = crate::engine::data_loader::DataLoader::load_geometry();
-    
-    // Build facilities map
-    let mut facilities_map = std::collections::HashMap::new();
-    for facility in facilities_vec {
-        facilities_map.insert(facility.id.clone(), facility);
-    }
-    
-    // Convert recipes to solver format
-    let solver_recipes: Vec<crate::engine::recipe_solver::Recipe> = recipes_vec.iter().map(|r| {
-        crate::engine::recipe_solver::Recipe {
-            id: r.id.clone(),
-            name: r.name.clone(),
-            inputs: r.inputs.iter().map(|i| crate::engine::recipe_solver::RecipeInput {
-                item_id: i.item_id.clone(),
-                amount: i.amount as f64,
-            }).collect(),
-            outputs: r.outputs.iter().map(|o| crate::engine::recipe_solver::RecipeOutput {
-                item_id: o.item_id.clone(),
-                amount: o.amount as f64,
-            }).collect(),
-            facility_id: r.facility_id.clone(),
-            crafting_time: r.crafting_time as f64,
-        }
-    }).collect();
-    
-    // Solve for requirements
-    let solver = crate::engine::recipe_solver::RecipeSolver::new(solver_recipes, facilities_map);
-    let plan = solver.solve(
-        request.target_items.clone(),
-        request.plate_width,
-        request.plate_height,
-    ).map_err(|e| e.to_string())?;
-
-    println!("DEBUG: Plan generated. Power: {}, Limited: {}", plan.total_power, plan.constraint_limited);
-    println!("DEBUG: Production plan requires {} facility types", plan.required_facilities.len());
-    
-    // Generate layouts - Note: PAC is now handled automatically in layout_generator
-    let constraints = crate::engine::layout_generator::LayoutConstraints {
-        plate_width: request.plate_width,
-        plate_height: request.plate_height,
-        power_source_type: "pac".to_string(), // Consistently use 'pac'
-        power_source_x: 0, // Will be set by generator
-        power_source_y: 0, // Will be set by generator
-        max_power_budget: None,
-    };
+fn generate_optimal_layouts(state: State<'_, AppState>, request: GenerateLayoutsRequest) -> Result<Vec<crate::engine::layout_generator::LayoutCandidate>, EngineError> {
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+
+    // Layouts are laid out for the plate being edited, around its obstacles; the solver
+    // packs against the same constraints so the plan only asks for what fits
+    let mut constraints = crate::engine::layout_generator::LayoutConstraints::for_plate(request.plate_width, request.plate_height);
+    constraints.terrain = state.plates.read_or_recover().active().grid.terrain.clone();
+    let plan = solve_plan(&state, request.target_items.clone(), &constraints)?;
     
     let generator = crate::engine::layout_generator::LayoutGenerator::new(constraints, geometry);
     
@@ -141,7 +128,7 @@ fn generate_optimal_layouts(request: GenerateLayoutsRequest) -> Result<Vec<crate
         .collect();
     
     // Use actual rates from plan (potentially constrained)
-    let actual_target_items: Vec<(String, f64)> = plan.actual_rates.iter()
+    let actual_target_items: Vec<(String, crate::engine::rate::Rate)> = plan.actual_rates.iter()
         .map(|(k, v)| (k.clone(), *v))
         .collect();
 
@@ -156,110 +143,481 @@ fn generate_optimal_layouts(request: GenerateLayoutsRequest) -> Result<Vec<crate
         candidate.limiting_factor = plan.limiting_factor.clone();
     }
 
-    println!("DEBUG: Generator returned {} candidates", candidates.len());
-    
     // Take top 5
     candidates.truncate(5);
     
     if candidates.is_empty() {
-        return Err("No valid layouts could be generated within the given constraints. Try a larger board.".to_string());
+        return Err(EngineError::infeasible("No valid layouts could be generated within the given constraints. Try a larger board."));
     }
-    
-    println!("DEBUG: Generated {} layout candidates", candidates.len());
-    
+
     Ok(candidates)
 }
 
 #[tauri::command]
-fn update_config(config: serde_json::Value) {
-    println!("DEBUG: update_config called");
-    crate::engine::data_loader::DataLoader::update_config(config);
+fn update_config(config: serde_json::Value) -> Result<(), EngineError> {
+    tracing::info!("config updated");
+    crate::engine::data_loader::DataLoader::update_config(config)
 }
 
+/// Frontend messages go to the same log as the backend's, under the `frontend` target
 #[tauri::command]
-fn log_to_terminal(msg: String) {
-    println!("[Frontend]: {}", msg);
+fn log_to_terminal(msg: String, level: Option<String>) {
+    match level.as_deref() {
+        Some("error") => tracing::error!(target: "frontend", "{}", msg),
+        Some("warn") => tracing::warn!(target: "frontend", "{}", msg),
+        Some("debug") => tracing::debug!(target: "frontend", "{}", msg),
+        _ => tracing::info!(target: "frontend", "{}", msg),
+    }
 }
 
+/// Changes the log filter at runtime, e.g. `info,endfield_engine::logistics_engine=trace`
 #[tauri::command]
-fn tick_simulation(state: State<'_, AppState>) -> Vec<crate::engine::facility::PlacedFacility> {
-    // println!("DEBUG: tick_simulation called");
-    let mut grid = state.grid.lock().unwrap();
-    
-    // Run simulation tick (Fixed 50ms intervals)
-    crate::engine::logistics_engine::LogisticsEngine::tick(&mut grid, &state.recipes);
-    
-    // Return updated state immediately for frontend sync
-    grid.placed_facilities.clone()
+fn set_log_filter(logging: State<'_, Logging>, filter: String) -> Result<(), EngineError> {
+    logging.set_filter(&filter)?;
+    tracing::info!(%filter, "log filter changed");
+    Ok(())
 }
 
+/// Folder with the rotated log files, for attaching to bug reports
 #[tauri::command]
-fn manual_inject_item(state: State<'_, AppState>, instance_id: String, slot_index: usize, item_id: String, quantity: u32) -> Result<String, String> {
-    println!("DEBUG: manual_inject_item called: {} x{} -> {} [Slot {}]", item_id, quantity, instance_id, slot_index);
-    let mut grid = state.grid.lock().unwrap();
-    
-    if let Some(fac) = grid.placed_facilities.iter_mut().find(|f| f.instance_id == instance_id) {
-        // Ensure buffer size covers the requested slot
-        while fac.input_buffer.len() <= slot_index {
-            fac.input_buffer.push(crate::engine::facility::BufferSlot {
-                item_id: "".to_string(), // Empty placeholder
-                quantity: 0,
-                source_port_id: None,
-                target_port_id: None, 
-            });
+fn get_log_directory(logging: State<'_, Logging>) -> Option<String> {
+    logging.directory().map(|d| d.display().to_string())
+}
+
+/// Steps the simulation on its own thread and pushes what changed to the frontend,
+/// so the UI no longer has to poll and clone every facility. This thread is the only
+/// writer that advances the simulation; manual ticks arrive as `SimulationRequest`s.
+fn simulation_loop(app: tauri::AppHandle, requests: mpsc::Receiver<SimulationRequest>) {
+    let state = app.state::<AppState>();
+    let mut last_sent: Option<SimulationSnapshot> = None;
+    let mut next_tick = Instant::now();
+    loop {
+        let clock = state.clock.read_or_recover().clone();
+        next_tick += clock.interval();
+        let now = Instant::now();
+        if next_tick <= now {
+            // Fell behind (e.g. a long solve held the lock); don't try to catch up
+            next_tick = now;
+        }
+
+        // Wait out the interval, serving manual ticks in the meantime
+        loop {
+            let now = Instant::now();
+            if next_tick <= now {
+                break;
+            }
+            match requests.recv_timeout(next_tick - now) {
+                Ok(SimulationRequest::Tick(reply)) => {
+                    let result = guarded_step(|| {
+                        let mut plates = state.plates.write_or_recover();
+                        plates.step(&state.recipes, clock.step_seconds());
+                        plates.active().grid.placed_facilities.clone()
+                    });
+                    let _ = reply.send(result);
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        if clock.paused {
+            continue;
         }
-        
-        // Overwrite standard slot data logic: direct injection implies filling the slot
-        fac.input_buffer[slot_index] = crate::engine::facility::BufferSlot {
-            item_id: item_id.clone(),
-            quantity,
-            source_port_id: None,
-            target_port_id: None,
+
+        let snapshot = guarded_step(|| {
+            let mut plates = state.plates.write_or_recover();
+            plates.step(&state.recipes, clock.step_seconds());
+            SimulationSnapshot::capture(plates.active())
+        });
+        let snapshot = match snapshot {
+            Ok(snapshot) => snapshot,
+            Err(_) => {
+                // Likely to fail again on the same state; stop until the user resumes
+                state.clock.write_or_recover().paused = true;
+                last_sent = None; // Next delta is a full resync
+                continue;
+            }
         };
-        
-        println!("DEBUG: Injected {} into slot {}", item_id, slot_index);
-        return Ok(format!("Successfully injected {} into slot {}", item_id, slot_index));
+        let delta = snapshot.delta_from(last_sent.as_ref());
+        if !delta.is_empty() {
+            if let Err(e) = app.emit(SIMULATION_EVENT, delta) {
+                tracing::warn!(error = %e, "failed to emit {}", SIMULATION_EVENT);
+            }
+        }
+        last_sent = Some(snapshot);
     }
-    
-    Err(format!("Facility {} not found", instance_id))
 }
 
+/// Runs one simulation step, turning a panic into an error so the thread survives it.
+/// The panic leaves the plates lock poisoned; the next access recovers it.
+fn guarded_step<T>(step: impl FnOnce() -> T) -> Result<T, EngineError> {
+    std::panic::catch_unwind(AssertUnwindSafe(step)).map_err(|panic| {
+        let message = panic.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        tracing::error!(%message, "simulation step panicked");
+        EngineError::invalid(format!("Simulation step failed: {}", message))
+    })
+}
+
+#[tauri::command]
+fn get_simulation_clock(state: State<'_, AppState>) -> SimulationClock {
+    state.clock.read_or_recover().clone()
+}
+
+#[tauri::command]
+fn pause_simulation(state: State<'_, AppState>) -> SimulationClock {
+    let mut clock = state.clock.write_or_recover();
+    clock.paused = true;
+    clock.clone()
+}
+
+#[tauri::command]
+fn resume_simulation(state: State<'_, AppState>) -> SimulationClock {
+    let mut clock = state.clock.write_or_recover();
+    clock.paused = false;
+    clock.clone()
+}
+
+/// `speed` multiplies simulated time per tick; `tick_rate` is ticks (and events) per second
+#[tauri::command]
+fn set_simulation_speed(state: State<'_, AppState>, speed: Option<f64>, tick_rate: Option<f64>) -> Result<SimulationClock, EngineError> {
+    let mut clock = state.clock.write_or_recover();
+    if let Some(speed) = speed {
+        clock.set_speed(speed)?;
+    }
+    if let Some(tick_rate) = tick_rate {
+        clock.set_tick_rate(tick_rate)?;
+    }
+    Ok(clock.clone())
+}
+
+/// Manual step of one tick's simulated time, e.g. while the background loop is paused.
+/// Runs on the simulation thread.
+#[tauri::command]
+fn tick_simulation(state: State<'_, AppState>) -> Result<Vec<crate::engine::facility::PlacedFacility>, EngineError> {
+    let (reply, result) = mpsc::channel();
+    state.simulation.send(SimulationRequest::Tick(reply))
+        .map_err(|_| EngineError::invalid("Simulation thread is not running"))?;
+    result.recv().map_err(|_| EngineError::invalid("Simulation thread is not running"))?
+}
+
+#[tauri::command]
+fn get_simulation_report(state: State<'_, AppState>) -> crate::engine::simulation_stats::SimulationReport {
+    let plates = state.plates.read_or_recover();
+    let grid = &plates.active().grid;
+    let plan = state.last_plan.read_or_recover();
+    // Compare against the last plan the solver produced, if any
+    grid.stats.report(&grid.placed_facilities, &grid.logistics_edges, &state.recipes, plan.as_ref())
+}
+
+#[tauri::command]
+fn check_edges(state: State<'_, AppState>) -> Result<Vec<crate::engine::edge_validation::EdgeReport>, EngineError> {
+    let plates = state.plates.read_or_recover();
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+    Ok(plates.active().grid.check_edges(&geometry, &state.recipes))
+}
+
+#[tauri::command]
+fn manual_inject_item(state: State<'_, AppState>, instance_id: String, slot_index: usize, item_id: String, quantity: u32) -> Result<String, EngineError> {
+    let mut plates = state.plates.write_or_recover();
+    let grid = &mut plates.active_mut().grid;
+
+    let capacity = grid.slot_capacity;
+    let fac = input_slot_owner(grid, &instance_id, slot_index)?;
+    let quantity = crate::engine::logistics_engine::LogisticsEngine::inject(fac, slot_index, &item_id, quantity, capacity, &state.recipes)?;
+
+    tracing::debug!(%instance_id, slot_index, %item_id, quantity, "injected item");
+    Ok(format!("Successfully injected {} into slot {}", item_id, slot_index))
+}
+
+/// The facility `instance_id`, checked to have an input slot at `slot_index`
+/// (slots are fixed per facility, see GridState::resize_buffers)
+fn input_slot_owner<'a>(grid: &'a mut crate::engine::grid::GridState, instance_id: &str, slot_index: usize) -> Result<&'a mut crate::engine::facility::PlacedFacility, EngineError> {
+    let fac = grid.placed_facilities.iter_mut()
+        .find(|f| f.instance_id == instance_id)
+        .ok_or_else(|| EngineError::missing_facility(instance_id))?;
+    if slot_index >= fac.input_buffer.len() {
+        return Err(EngineError::InvalidSlot {
+            instance_id: instance_id.to_string(),
+            slot_index,
+            slot_count: fac.input_buffer.len(),
+        });
+    }
+    Ok(fac)
+}
+
+#[tauri::command]
+fn manual_clear_slot(state: State<'_, AppState>, instance_id: String, slot_index: usize) -> Result<String, EngineError> {
+    let mut plates = state.plates.write_or_recover();
+    let grid = &mut plates.active_mut().grid;
+    input_slot_owner(grid, &instance_id, slot_index)?.input_buffer[slot_index].clear();
+    tracing::debug!(%instance_id, slot_index, "cleared slot");
+    Ok("Slot cleared".to_string())
+}
+
+#[tauri::command]
+fn set_recipe_lock(state: State<'_, AppState>, instance_id: String, recipe_id: Option<String>) -> Result<String, EngineError> {
+    tracing::debug!(%instance_id, ?recipe_id, "set recipe lock");
+    let mut plates = state.plates.write_or_recover();
+    let grid = &mut plates.active_mut().grid;
+    let fac = grid.placed_facilities.iter_mut()
+        .find(|f| f.instance_id == instance_id)
+        .ok_or_else(|| EngineError::missing_facility(&instance_id))?;
+
+    // None goes back to auto-detecting the recipe from the input buffer
+    if let Some(recipe_id) = &recipe_id {
+        let recipe = state.recipes.iter()
+            .find(|r| r.id == *recipe_id)
+            .ok_or_else(|| EngineError::MissingRecipe { recipe_id: recipe_id.clone() })?;
+        if recipe.facility_id != fac.facility_id {
+            return Err(EngineError::RecipeMismatch { recipe_id: recipe_id.clone(), facility_id: fac.facility_id.clone() });
+        }
+    }
+
+    fac.locked_recipe_id = recipe_id;
+    Ok(match &fac.locked_recipe_id {
+        Some(id) => format!("Locked {} to {}", instance_id, id),
+        None => format!("{} auto-detects its recipe", instance_id),
+    })
+}
+
+/// Solves for the targets without generating layouts. The plan becomes the one
+/// simulation reports and `export_plan` refer to.
 #[tauri::command]
-fn manual_clear_slot(state: State<'_, AppState>, instance_id: String, slot_index: usize) -> Result<String, String> {
-    let mut grid = state.grid.lock().unwrap();
-    if let Some(fac) = grid.placed_facilities.iter_mut().find(|f| f.instance_id == instance_id) {
-        if slot_index < fac.input_buffer.len() {
-            fac.input_buffer[slot_index].item_id = "".to_string();
-            fac.input_buffer[slot_index].quantity = 0;
-            println!("DEBUG: Cleared slot {}", slot_index);
-            return Ok("Slot cleared".to_string());
+fn solve_production(state: State<'_, AppState>, target_items: Vec<(String, crate::engine::rate::Rate)>, plate_width: i32, plate_height: i32) -> Result<crate::engine::recipe_solver::ProductionPlan, EngineError> {
+    solve_plan(&state, target_items, &crate::engine::layout_generator::LayoutConstraints::for_plate(plate_width, plate_height))
+}
+
+/// Renders `plan` (or the last solved plan) as a report. Writes it to `path` when given,
+/// otherwise returns the text.
+#[tauri::command]
+fn export_plan(
+    state: State<'_, AppState>,
+    format: crate::engine::plan_export::ExportFormat,
+    plan: Option<crate::engine::recipe_solver::ProductionPlan>,
+    path: Option<String>,
+) -> Result<String, EngineError> {
+    let plan = match plan {
+        Some(plan) => plan,
+        None => state.last_plan.read_or_recover().clone().ok_or("No production plan solved yet")?,
+    };
+    let report = plan.export(format)?;
+    match path {
+        Some(path) => {
+            std::fs::write(&path, &report).map_err(|e| EngineError::invalid(format!("Failed to write {}: {}", path, e)))?;
+            Ok(format!("Exported plan to {}", path))
         }
+        None => Ok(report),
+    }
+}
+
+#[tauri::command]
+fn save_project(state: State<'_, AppState>, path: String) -> Result<String, EngineError> {
+    tracing::info!(%path, "saving project");
+    let plates = state.plates.read_or_recover();
+    let targets = state.targets.read_or_recover();
+    crate::engine::project::ProjectFile::capture(&plates, &targets).save(std::path::Path::new(&path))?;
+    Ok(format!("Saved {} plates to {}", plates.plates.len(), path))
+}
+
+#[tauri::command]
+fn load_project(state: State<'_, AppState>, path: String) -> Result<crate::engine::project::ProjectFile, EngineError> {
+    tracing::info!(%path, "loading project");
+    let project = crate::engine::project::ProjectFile::load(std::path::Path::new(&path))?;
+
+    let config = crate::engine::data_loader::DataLoader::load_config()?;
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+    *state.plates.write_or_recover() = project.restore(&config, &geometry)?;
+    *state.targets.write_or_recover() = project.targets.clone();
+    Ok(project)
+}
+
+#[tauri::command]
+fn copy_blueprint(state: State<'_, AppState>, x: i32, y: i32, width: u32, height: u32) -> Result<String, EngineError> {
+    tracing::debug!(x, y, width, height, "copy blueprint");
+    let plates = state.plates.read_or_recover();
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+    let blueprint = crate::engine::blueprint::Blueprint::capture(&plates.active().grid, &geometry, x, y, width, height);
+    if blueprint.facilities.is_empty() {
+        return Err(EngineError::invalid("Selection contains no facilities"));
     }
-    Ok("Slot empty or invalid index".to_string())
+    blueprint.encode()
+}
+
+#[tauri::command]
+fn paste_blueprint(state: State<'_, AppState>, blueprint: String, x: i32, y: i32, rotation: u32) -> Result<Vec<crate::engine::facility::PlacedFacility>, EngineError> {
+    tracing::debug!(x, y, rotation, "paste blueprint");
+    let blueprint = crate::engine::blueprint::Blueprint::decode(&blueprint)?;
+    let mut plates = state.plates.write_or_recover();
+    let plate = plates.active_mut();
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+    let edits = blueprint.paste_edits(&plate.grid, &geometry, x, y, rotation)?;
+
+    // One undo step for the whole paste
+    let changes = plate.history.apply(&mut plate.grid, "Paste blueprint", edits, &geometry)?;
+    let ids: Vec<String> = changes.iter().flat_map(|c| c.placed.iter().map(|f| f.instance_id.clone())).collect();
+
+    // Hand the new facilities back so the frontend can add them
+    Ok(plate.grid.placed_facilities.iter().filter(|f| ids.contains(&f.instance_id)).cloned().collect())
+}
+
+fn apply_grid_edit(state: &AppState, edit: crate::engine::grid_edit::GridEdit) -> Result<crate::engine::grid_edit::GridChange, EngineError> {
+    tracing::debug!(?edit, "grid edit");
+    let mut plates = state.plates.write_or_recover();
+    let plate = plates.active_mut();
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+    let label = edit.describe();
+    let mut changes = plate.history.apply(&mut plate.grid, &label, vec![edit], &geometry)?;
+    Ok(changes.pop().unwrap_or_default())
+}
+
+/// Empty list when there was nothing to undo
+#[tauri::command]
+fn undo(state: State<'_, AppState>) -> Result<Vec<crate::engine::grid_edit::GridChange>, EngineError> {
+    let mut plates = state.plates.write_or_recover();
+    let plate = plates.active_mut();
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+    Ok(plate.history.undo(&mut plate.grid, &geometry)?.unwrap_or_default())
+}
+
+#[tauri::command]
+fn redo(state: State<'_, AppState>) -> Result<Vec<crate::engine::grid_edit::GridChange>, EngineError> {
+    let mut plates = state.plates.write_or_recover();
+    let plate = plates.active_mut();
+    let geometry = crate::engine::data_loader::DataLoader::load_geometry()?;
+    Ok(plate.history.redo(&mut plate.grid, &geometry)?.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_history(state: State<'_, AppState>) -> crate::engine::history::HistorySummary {
+    state.plates.read_or_recover().active().history.summary()
+}
+
+#[tauri::command]
+fn place_facility(state: State<'_, AppState>, facility_id: String, x: i32, y: i32, rotation: u32) -> Result<crate::engine::grid_edit::GridChange, EngineError> {
+    let facility = crate::engine::facility::PlacedFacility {
+        rotation,
+        ..crate::engine::facility::PlacedFacility::new("", &facility_id, x, y)
+    };
+    apply_grid_edit(&state, crate::engine::grid_edit::GridEdit::PlaceFacility { facility })
+}
+
+#[tauri::command]
+fn move_facility(state: State<'_, AppState>, instance_id: String, x: i32, y: i32) -> Result<crate::engine::grid_edit::GridChange, EngineError> {
+    apply_grid_edit(&state, crate::engine::grid_edit::GridEdit::MoveFacility { instance_id, x, y })
+}
+
+#[tauri::command]
+fn rotate_facility(state: State<'_, AppState>, instance_id: String, rotation: u32) -> Result<crate::engine::grid_edit::GridChange, EngineError> {
+    apply_grid_edit(&state, crate::engine::grid_edit::GridEdit::RotateFacility { instance_id, rotation })
+}
+
+#[tauri::command]
+fn remove_facility(state: State<'_, AppState>, instance_id: String) -> Result<crate::engine::grid_edit::GridChange, EngineError> {
+    apply_grid_edit(&state, crate::engine::grid_edit::GridEdit::RemoveFacility { instance_id })
+}
+
+#[tauri::command]
+fn add_edge(state: State<'_, AppState>, edge: crate::engine::logistics::LogisticsEdge) -> Result<crate::engine::grid_edit::GridChange, EngineError> {
+    apply_grid_edit(&state, crate::engine::grid_edit::GridEdit::AddEdge { edge })
+}
+
+#[tauri::command]
+fn remove_edge(state: State<'_, AppState>, key: String) -> Result<crate::engine::grid_edit::GridChange, EngineError> {
+    apply_grid_edit(&state, crate::engine::grid_edit::GridEdit::RemoveEdge { key })
+}
+
+#[tauri::command]
+fn list_plates(state: State<'_, AppState>) -> crate::engine::plates::ProjectSummary {
+    // Per-plate and combined production, plus the links between plates
+    state.plates.read_or_recover().summary()
+}
+
+/// Size comes from `preset` (config preset id or name) when given, else from width/height
+#[tauri::command]
+fn add_plate(state: State<'_, AppState>, name: String, preset: Option<String>, width: Option<u32>, height: Option<u32>) -> Result<String, EngineError> {
+    tracing::debug!(%name, ?preset, "add plate");
+    let config = crate::engine::data_loader::DataLoader::load_config()?;
+    let (width, height) = match &preset {
+        Some(preset) => crate::engine::plates::PlateSet::preset_size(&config, preset)
+            .ok_or_else(|| EngineError::MissingPreset { preset: preset.clone() })?,
+        None => (width.unwrap_or(32), height.unwrap_or(32)),
+    };
+    state.plates.write_or_recover().add_plate(&name, width, height, &config)?;
+    Ok(format!("Added plate {} ({}x{})", name, width, height))
+}
+
+/// Adds a plate from a plate definition file (terrain map with obstacles and resource nodes)
+#[tauri::command]
+fn load_plate_definition(state: State<'_, AppState>, path: String) -> Result<String, EngineError> {
+    tracing::debug!(%path, "load plate definition");
+    let definition = crate::engine::terrain::PlateDefinition::load(std::path::Path::new(&path))?;
+    let config = crate::engine::data_loader::DataLoader::load_config()?;
+    state.plates.write_or_recover().add_plate_from_definition(&definition, &config)?;
+    let (width, height) = definition.size();
+    Ok(format!("Added plate {} ({}x{})", definition.name, width, height))
+}
+
+#[tauri::command]
+fn remove_plate(state: State<'_, AppState>, name: String) -> Result<String, EngineError> {
+    state.plates.write_or_recover().remove_plate(&name)?;
+    Ok(format!("Removed plate {}", name))
+}
+
+/// Makes `name` the plate that edits and `get_grid_state` refer to
+#[tauri::command]
+fn select_plate(state: State<'_, AppState>, name: String) -> Result<crate::engine::grid::GridState, EngineError> {
+    let mut plates = state.plates.write_or_recover();
+    plates.select(&name)?;
+    Ok(plates.active().grid.clone())
+}
+
+#[tauri::command]
+fn add_plate_link(state: State<'_, AppState>, link: crate::engine::plates::PlateLink) -> Result<String, EngineError> {
+    let key = link.key();
+    state.plates.write_or_recover().add_link(link)?;
+    Ok(key)
+}
+
+#[tauri::command]
+fn remove_plate_link(state: State<'_, AppState>, key: String) -> Result<crate::engine::plates::PlateLink, EngineError> {
+    state.plates.write_or_recover().remove_link(&key)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    println!("DEBUG: Starting Endfield lib run()");
-    let config = crate::engine::data_loader::DataLoader::load_config();
-    let recipes = crate::engine::data_loader::DataLoader::load_recipes(); // Load recipes here
-    println!("DEBUG: Config loaded in run(): {:?}", config);
-    println!("DEBUG: Loaded {} recipes", recipes.len());
-    
-    println!("DEBUG: Initializing Optimizer (WGPU) - Optional -- DISABLED FOR DEBUGGING");
+    // Nothing works without the database, so a broken one stops the app here
+    let config = crate::engine::data_loader::DataLoader::load_config()
+        .unwrap_or_else(|e| panic!("CRITICAL: {}", e));
+    let recipes = crate::engine::data_loader::DataLoader::load_recipes()
+        .unwrap_or_else(|e| panic!("CRITICAL: {}", e)); // Load recipes here
+    let mut log_options = LogOptions::from_config("endfield", &config);
+    let recipe_count = recipes.len();
+    let (simulation, requests) = mpsc::channel();
+
     // let optimizer = pollster::block_on(Optimizer::new());
-    let optimizer: Option<Optimizer> = None;
-    if optimizer.is_some() {
-        println!("DEBUG: Optimizer initialized successfully");
-    } else {
-        println!("DEBUG: Optimizer initialization skipped (Debugging)");
-    }
-    
+    let optimizer: Option<Optimizer> = None; // WGPU optimizer disabled for debugging
+
     tauri::Builder::default()
         .manage(AppState {
-            grid: Mutex::new(GridState::new(&config)),
+            plates: RwLock::new(crate::engine::plates::PlateSet::new(&config)),
             optimizer,
             recipes,
+            last_plan: RwLock::new(None),
+            targets: RwLock::new(Vec::new()),
+            clock: RwLock::new(SimulationClock::from_config(&config)),
+            simulation,
+        })
+        .setup(move |app| {
+            // Log files live in the OS log folder for the app (rotated daily)
+            log_options.directory = app.path().app_log_dir().ok();
+            let logging = Logging::init(log_options)?;
+            tracing::info!(recipes = recipe_count, log_directory = ?logging.directory(), "Endfield sandbox starting");
+            app.manage(logging);
+
+            let handle = app.handle().clone();
+            std::thread::spawn(move || simulation_loop(handle, requests));
+            Ok(())
         })
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
@@ -270,9 +628,40 @@ pub fn run() {
             get_power_status, 
             generate_optimal_layouts, 
             log_to_terminal,
+            set_log_filter,
+            get_log_directory,
             tick_simulation,
+            get_simulation_clock,
+            pause_simulation,
+            resume_simulation,
+            set_simulation_speed,
             manual_inject_item,
-            manual_clear_slot // NEW COMMAND
+            manual_clear_slot, // NEW COMMAND
+            get_simulation_report,
+            set_recipe_lock,
+            solve_production,
+            export_plan,
+            save_project,
+            load_project,
+            copy_blueprint,
+            paste_blueprint,
+            place_facility,
+            move_facility,
+            rotate_facility,
+            remove_facility,
+            add_edge,
+            remove_edge,
+            undo,
+            redo,
+            get_history,
+            check_edges,
+            list_plates,
+            add_plate,
+            load_plate_definition,
+            remove_plate,
+            select_plate,
+            add_plate_link,
+            remove_plate_link
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -4,9 +4,10 @@ import {
   Menu, Box, Link2, MousePointer2, Move, Eraser,
   ChevronDown, ChevronRight, X, Info, Check, PlusCircle, Zap, Cpu,
   Maximize2, Filter, Activity, CircleDot, Trash2, Search, Package,
-  ArrowRightCircle, Timer
+  ArrowRightCircle, Timer, Lock
 } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { clsx, type ClassValue } from "clsx";
 import { twMerge } from "tailwind-merge";
 import { debugLog } from "./utils/logger";
@@ -300,9 +301,11 @@ export default function App() {
     (window as any).clearDragState = clearDragState;
     appDataRef.current = appData; // Keep ref in sync
 
-    const interval = setInterval(() => {
-      invoke("get_power_status").then(setPowerStatus);
-    }, 1000);
+    // Power stats arrive with the backend's simulation-delta events; fetch once for the initial state
+    invoke("get_power_status").then(setPowerStatus);
+    const unlistenPower = listen<any>("simulation-delta", (event) => {
+      if (event.payload.power) setPowerStatus(event.payload.power);
+    });
 
     const handleGlobalMouseMove = (e: MouseEvent) => {
       mousePos.current = { x: e.clientX, y: e.clientY };
@@ -376,7 +379,7 @@ export default function App() {
     window.addEventListener('facility-selected', handleFacilitySelected);
 
     return () => {
-      clearInterval(interval);
+      unlistenPower.then(unlisten => unlisten());
       window.removeEventListener("click", closeMenu);
       window.removeEventListener("mousemove", handleGlobalMouseMove);
       window.removeEventListener('mouse-grid-update', handleMouseGrid);
@@ -481,6 +484,15 @@ export default function App() {
     }
   };
 
+  // Clicking the locked recipe again unlocks it (back to auto-detect)
+  const toggleRecipeLock = async (instanceId: string, recipeId: string, currentLock?: string | null) => {
+    try {
+      await invoke("set_recipe_lock", { instanceId, recipeId: currentLock === recipeId ? null : recipeId });
+    } catch (e) {
+      console.error("Failed to set recipe lock:", e);
+    }
+  };
+
   const theme = appData?.config?.theme || {
     panel_bg: "#2d2d2d",
     workspace_bg: "#1e1e1e",
@@ -794,16 +806,11 @@ export default function App() {
             const recipes = appData?.recipes?.filter((r: any) => r.facility_id === meta.id) || [];
             const getItem = (id: string) => appData?.items?.find((i: any) => i.id === id);
 
-            // Refined Recipe State Tracking (Timestamp-based for 1:1 accuracy)
+            // Recipe state comes straight from the simulation: progress is 0..1, remaining in seconds
             const activeRecipe = recipes.find((r: any) => r.id === pf?.active_recipe_id);
-            const totalTime = activeRecipe?.time || 1;
 
-            // Calculate real-time progress based on start timestamp
-            const nowSeconds = Date.now() / 1000;
-            const elapsedSinceStart = pf?.recipe_progress > 0 ? (nowSeconds - pf.recipe_progress) : 0;
-
-            const progressPercent = activeRecipe ? Math.min(100, (elapsedSinceStart / totalTime) * 100) : 0;
-            const remainingTime = activeRecipe ? Math.max(0, totalTime - elapsedSinceStart) : 0;
+            const progressPercent = activeRecipe ? Math.min(100, (pf?.recipe_progress || 0) * 100) : 0;
+            const remainingTime = activeRecipe ? Math.max(0, pf?.recipe_remaining || 0) : 0;
             const isProcessing = !!activeRecipe;
 
             return (
@@ -1055,7 +1062,16 @@ export default function App() {
                             <div className="p-3 space-y-3 bg-black/10 animate-in slide-in-from-top-2 duration-200 overflow-y-auto max-h-[25em] custom-scrollbar">
                               <div className="grid gap-[0.5em]">
                                 {recipes.map((r: any) => (
-                                  <div key={r.id} className="bg-[#1e1e1e] border border-[#333] p-[1em] rounded-md flex items-center justify-between gap-[1em] hover:bg-[#252525] hover:border-white/10 transition-colors group">
+                                  <div
+                                    key={r.id}
+                                    onClick={() => toggleRecipeLock(instanceId, r.id, pf.locked_recipe_id)}
+                                    title={pf.locked_recipe_id === r.id ? "Locked - click to auto-detect" : "Click to lock this recipe"}
+                                    className={cn(
+                                      "relative bg-[#1e1e1e] border border-[#333] p-[1em] rounded-md flex items-center justify-between gap-[1em] hover:bg-[#252525] hover:border-white/10 transition-colors group cursor-pointer",
+                                      pf.locked_recipe_id === r.id && "border-[#0078d7] hover:border-[#0078d7]"
+                                    )}
+                                  >
+                                    {pf.locked_recipe_id === r.id && <Lock size={10} className="absolute top-1 right-1 text-[#0078d7]" />}
                                     <div className="flex items-center gap-[0.8em]">
                                       {r.inputs.map((input: any, idx: number) => {
                                         const item = getItem(input.item_id);
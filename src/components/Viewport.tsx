@@ -72,15 +72,8 @@ export function Viewport({ appData, draggedFacilityId, onDropFinished }: { appDa
         draggedFacilityIdRef.current = draggedFacilityId;
     }, [draggedFacilityId]);
 
-    const { placedFacilities, edges, occupancyMap, addFacility, updateFacility, removeFacility, setMovingFacilityId, isColliding, stepSimulation } = useSandbox(appData);
-
-    // Simulation Loop (20 TPS)
-    useEffect(() => {
-        const interval = setInterval(() => {
-            stepSimulation();
-        }, 50);
-        return () => clearInterval(interval);
-    }, [stepSimulation]);
+    // Simulation runs in the backend loop; useSandbox applies its simulation-delta events
+    const { placedFacilities, edges, occupancyMap, addFacility, updateFacility, removeFacility, setMovingFacilityId, isColliding } = useSandbox(appData);
 
     const [selectedFacilityId, setSelectedFacilityId] = useState<string | null>(null);
     const occupancyMapRef = useRef<Map<string, any>>(occupancyMap);
@@ -1,5 +1,6 @@
 import { useState, useCallback, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { debugLog } from "../utils/logger";
 
 export interface PlacedFacility {
@@ -9,6 +10,7 @@ export interface PlacedFacility {
     y: number;
     rotation: number;
     port_settings?: { port_id: string, item_id: string }[];
+    locked_recipe_id?: string | null;
     input_buffer?: { item_id: string, source_port_id?: string, target_port_id?: string, quantity: number }[];
     output_buffer?: { item_id: string, source_port_id?: string, target_port_id?: string, quantity: number }[];
 }
@@ -51,7 +53,8 @@ export function useSandbox(appData?: any) {
                 x: Math.floor(f.x / GRID_SIZE),
                 y: Math.floor(f.y / GRID_SIZE),
                 rotation: f.rotation,
-                port_settings: f.port_settings || []
+                port_settings: f.port_settings || [],
+                locked_recipe_id: f.locked_recipe_id ?? null
             })),
             edges: currentEdges.map(e => ({
                 from_instance_id: e.fromId,
@@ -63,7 +66,11 @@ export function useSandbox(appData?: any) {
             }))
         })
             .then(() => debugLog("[useSandbox] Sync Success"))
-            .catch(err => debugLog("[useSandbox] Sync Failed (ERROR):", err));
+            // Errors are { code, message, context }; rejected layouts list their { kind, instance_id, ... } issues
+            .catch(err => {
+                debugLog("[useSandbox] Sync Failed (ERROR):", err);
+                if (err?.code === "rejected_layout") console.warn("[useSandbox] Backend rejected layout:", err.context.issues);
+            });
     }, [syncTrigger]); // Only re-run when manually triggered
 
     const isColliding = useCallback((pixelX: number, pixelY: number, widthInPixels: number, heightInPixels: number) => {
@@ -278,6 +285,33 @@ export function useSandbox(appData?: any) {
         debugLog("[useSandbox] Removed Facility:", instanceId);
     }, []);
 
+    // The backend runs the simulation loop and pushes only the facilities that changed
+    useEffect(() => {
+        const unlisten = listen<any>("simulation-delta", (event) => {
+            const { facilities, removed } = event.payload;
+            setPlacedFacilities(prev => {
+                const updates = new Map<string, any>(facilities.map((u: any) => [u.instance_id, u]));
+                // Only the dynamic state is sent; position and rotation stay as the frontend has them
+                return prev
+                    .filter(f => !removed.includes(f.instanceId))
+                    .map(f => {
+                        const u = updates.get(f.instanceId);
+                        return u ? {
+                            ...f,
+                            active_recipe_id: u.active_recipe_id,
+                            locked_recipe_id: u.locked_recipe_id,
+                            recipe_progress: u.recipe_progress,
+                            recipe_remaining: u.recipe_remaining,
+                            input_buffer: u.input_buffer,
+                            output_buffer: u.output_buffer
+                        } : f;
+                    });
+            });
+        });
+        return () => { unlisten.then(f => f()); };
+    }, []);
+
+    // Manual single step (e.g. while the backend loop is paused)
     const stepSimulation = useCallback(async () => {
         try {
             const updatedFacilities = await invoke<any[]>("tick_simulation");
@@ -301,7 +335,9 @@ export function useSandbox(appData?: any) {
                         rotation: u.rotation,
                         port_settings: u.port_settings,
                         active_recipe_id: u.active_recipe_id,
+                        locked_recipe_id: u.locked_recipe_id,
                         recipe_progress: u.recipe_progress,
+                        recipe_remaining: u.recipe_remaining,
                         input_buffer: u.input_buffer,
                         output_buffer: u.output_buffer
                     };